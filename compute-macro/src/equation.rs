@@ -1,8 +1,9 @@
-use proc_macro2::TokenStream;
-use syn::{Data, Fields};
+use proc_macro2::{Ident, Span, TokenStream};
+use syn::{Data, Fields, LitStr, Meta};
 
-use crate::field::Field;
-use crate::symbols::VARIABLE;
+use crate::field::{Field, Kind};
+use crate::parser;
+use crate::symbols::{EQUATION, UNIT, VARIABLE};
 
 fn log_structure(message: String) {
     logger::log(logger::LogStep::Structure, &message);
@@ -10,6 +11,7 @@ fn log_structure(message: String) {
 
 pub fn expand_derive_equation(input: &mut syn::DeriveInput) -> TokenStream {
     let mut variables = vec![];
+    let mut all_fields = vec![];
 
     log_structure(format!("Parsing equation {:?}", input.ident.to_string()));
 
@@ -18,11 +20,44 @@ pub fn expand_derive_equation(input: &mut syn::DeriveInput) -> TokenStream {
     if let Data::Struct(data) = input.clone().data {
         if let Fields::Named(fields) = data.fields {
             fields.named.iter().for_each(|field| {
+                all_fields.extend(field.ident.clone().map(|ident| (ident, field.ty.clone())));
                 for attr in &field.attrs {
                     if attr.path() == VARIABLE {
+                        // #[variable(unit = "m/s")]
+                        let mut unit = None;
+                        if let Meta::List(_) = attr.meta {
+                            let parsed = attr.parse_nested_meta(|meta| {
+                                if meta.path != UNIT {
+                                    return Err(meta.error("Unknown variable attribute, expected unit = \"...\""));
+                                }
+                                let literal: LitStr = meta.value()?.parse()?;
+                                if units::Unit::parse(&literal.value()).is_none() {
+                                    return Err(syn::Error::new(literal.span(), "Unknown unit"));
+                                }
+                                unit = Some(literal);
+                                Ok(())
+                            });
+                            if let Err(error) = parsed {
+                                struct_diagnostics.extend(error.to_compile_error());
+                            }
+                        }
+
+                        let Some(kind) = Kind::of(&field.ty) else {
+                            let message = "Variables must be EquationElement<S>, Option<f64> or a primitive number";
+                            struct_diagnostics.extend(syn::Error::new_spanned(&field.ty, message).to_compile_error());
+                            continue;
+                        };
                         if let Some(ident) = field.clone().ident {
                             log_structure(format!("Found variable: {:?}", ident.to_string()));
-                            variables.push(Field { name: ident });
+                            if let Some(unit) = &unit {
+                                log_structure(format!("Unit of {:?}: {:?}", ident.to_string(), unit.value()));
+                            }
+                            variables.push(Field {
+                                name: ident,
+                                ty: field.ty.clone(),
+                                kind,
+                                unit,
+                            });
                         }
                     } else {
                         break;
@@ -49,35 +84,401 @@ pub fn expand_derive_equation(input: &mut syn::DeriveInput) -> TokenStream {
         }
     }
 
+    // Every variable must be made of the same scalar, f64 unless specified, numbers being converted to it
+    let scalars: Vec<String> = variables
+        .iter()
+        .filter(|variable| variable.kind() != Kind::Number)
+        .map(|variable| match variable.scalar() {
+            Some(scalar) => quote! { #scalar }.to_string(),
+            None => "f64".to_string(),
+        })
+        .collect();
+    let scalar = variables
+        .iter()
+        .find(|variable| variable.kind() != Kind::Number)
+        .and_then(Field::scalar)
+        .map(|scalar| quote! { #scalar })
+        .unwrap_or_else(|| quote! { f64 });
+    if scalars.iter().any(|other| other != &scalars[0]) {
+        struct_diagnostics = quote! {
+            #struct_diagnostics
+            compile_error!("All the variables must use the same scalar type");
+        }
+    }
+    log_structure(format!("Scalar type: {}", scalar));
+
+    let names: Vec<_> = variables.iter().map(|variable| variable.name.clone()).collect();
+    let names_s: Vec<_> = names.iter().map(|name| name.to_string()).collect();
+
+    // MyEquationVar::Element2, to choose the unknown at runtime
+    let var = format_ident!("{}Var", ident);
+    let variants: Vec<_> = variables.iter().map(Field::variant).collect();
+    let vis = &input.vis;
+    let variable_named = |name: &Ident| variables.iter().zip(&variants).find(|(variable, _)| &variable.name == name);
+    // Hygienic, so that no field can be named like them
+    let unknown = Ident::new("unknown", Span::mixed_site());
+    let differentiated = Ident::new("differentiated", Span::mixed_site());
+
+    // Only Option<f64> can be computed, as the solutions are f64
+    for variable in &variables {
+        let optional = variable.optional_type().map(|ty| quote! { #ty }.to_string());
+        if variable.kind() == Kind::Optional && optional.as_deref() != Some("f64") {
+            let error = syn::Error::new_spanned(&variable.ty, "Optional variables must be Option<f64>");
+            struct_diagnostics.extend(error.to_compile_error());
+        }
+    }
+
+    // The copy of a variable that relations are written with, named after its field and in its unit
+    // It is the unknown if it is the variable given, and a known is lowered as an unknown if it is differentiated
+    let lowered = |variable: &Field, variant: &Ident| {
+        let name = &variable.name;
+        let name_s = name.to_string();
+        let element = variable.element(&quote! { self.#name }, &scalar);
+        let unknown_element = variable.unknown_element(&quote! { self.#name });
+        let unit = variable.unit.as_ref().map(|unit| {
+            quote! {
+                let element = match element.unit() {
+                    Some(_) => element,
+                    None => element.with_unit(#unit).expect("the unit is checked by the derive macro"),
+                };
+            }
+        });
+        quote! {{
+            let element = if #unknown == Some(#var::#variant) {
+                #unknown_element
+            } else {
+                #element
+            };
+            let element = match element.name() {
+                Some(_) => element,
+                None => element.with_name(#name_s),
+            };
+            #unit
+            match #differentiated.iter().position(|known| *known == #name_s) {
+                Some(index) => element.differentiated(index),
+                None => element,
+            }
+        }}
+    };
+
+    // #[equation("element1 = element2 + element3")] writes 'auto_compute'
+    let equations: Vec<_> = input.attrs.iter().filter(|attr| attr.path() == EQUATION).collect();
+    if let Some(extra) = equations.get(1) {
+        let error = syn::Error::new_spanned(extra, "There can only be one #[equation(\"...\")]");
+        struct_diagnostics.extend(error.to_compile_error());
+    }
+    let equation_relation = if let Some(attr) = equations.first() {
+        let relation = attr.parse_args::<LitStr>().and_then(|literal| {
+            let (lhs, rhs) = parser::parse(&literal)?;
+            let written = lhs.fields().into_iter().chain(rhs.fields()).collect::<Vec<_>>();
+            for (name, bytes) in &written {
+                if !all_fields.iter().any(|(field, _)| field == name) {
+                    return Err(parser::error(&literal, bytes.clone(), &format!("Unknown field '{}'", name)));
+                }
+            }
+            log_structure(format!("Equation: {:?}", literal.value()));
+
+            // Every field written is bound once, so that a variable written twice is the same unknown
+            let mut bindings = quote! {};
+            for (name, _) in all_fields.iter().filter(|(field, _)| written.iter().any(|(name, _)| field == name)) {
+                let Some((variable, variant)) = variable_named(name) else {
+                    bindings.extend(quote! { let #name = self.#name.clone(); });
+                    continue;
+                };
+                let lowered = lowered(variable, variant);
+                bindings.extend(quote! { let #name = #lowered; });
+            }
+            let (lhs, rhs) = (lhs.expression(&literal, &scalar), rhs.expression(&literal, &scalar));
+            Ok(quote! {
+                #bindings
+                (#lhs).equals(#rhs)
+            })
+        });
+        // The trait is still implemented after an error, so that it is the only one reported
+        let relation = relation.unwrap_or_else(|error| {
+            struct_diagnostics.extend(error.to_compile_error());
+            quote! { unreachable!("the equation does not compile") }
+        });
+        quote! {
+            // The relation written in #[equation("...")], a variable being replaced by the unknown if given,
+            // and the knowns named in 'differentiated' lowered as unknowns to differentiate the roots
+            #[allow(unused_variables)]
+            fn equation_relation(
+                &self,
+                #unknown: Option<#var>,
+                #differentiated: &[&str],
+            ) -> compute::relation::Relation<#scalar> {
+                #relation
+            }
+        }
+    } else {
+        if variables.iter().any(|variable| variable.kind() != Kind::Element) {
+            struct_diagnostics = quote! {
+                #struct_diagnostics
+                compile_error!("Variables that are not EquationElement need an #[equation(\"...\")]");
+            }
+        }
+        // Only elements can be lowered as copies, the other variables being reported above
+        let (elements, copies): (Vec<_>, Vec<_>) = variables
+            .iter()
+            .zip(&variants)
+            .filter(|(variable, _)| variable.kind() == Kind::Element)
+            .map(|(variable, variant)| (&variable.name, lowered(variable, variant)))
+            .unzip();
+        quote! {
+            // The relation of 'auto_compute', written with the copies of the fields that 'equation_relation'
+            // with an #[equation("...")] would write
+            #[allow(unused_variables)]
+            fn equation_relation(
+                &self,
+                #unknown: Option<#var>,
+                #differentiated: &[&str],
+            ) -> compute::relation::Relation<#scalar> {
+                compute::equation::Lowering::<#scalar>::new()
+                    #(.with_field(&self.#elements, #copies))*
+                    .apply(|| compute::equation::EquationAutoCompute::<#scalar>::auto_compute(self))
+            }
+        }
+    };
+    let auto_compute = if equations.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            impl compute::equation::EquationAutoCompute<#scalar> for #ident {
+                fn auto_compute(&self) -> compute::relation::Relation<#scalar> {
+                    self.equation_relation(None, &[])
+                }
+            }
+        }
+    };
+
+    // The fields that are not variables, copied as they are when the unknown is chosen
+    let (others, other_types): (Vec<_>, Vec<_>) = all_fields
+        .iter()
+        .filter(|(name, _)| !names.contains(name))
+        .map(|(name, ty)| (name, ty))
+        .unzip();
+    let value_types: Vec<_> = variables.iter().map(|variable| variable.value_type(&scalar)).collect();
+    let is_unknown: Vec<_> = variables
+        .iter()
+        .map(|variable| {
+            let name = &variable.name;
+            variable.is_unknown(&quote! { self.#name })
+        })
+        .collect();
+    let knowns: Vec<_> = variables.iter().map(|variable| variable.known(&quote! { value })).collect();
+    let unknowns: Vec<_> = variables.iter().map(Field::unknown).collect();
+    let numbers: Vec<_> = variables
+        .iter()
+        .zip(&variants)
+        .filter(|(variable, _)| variable.kind() == Kind::Number)
+        .map(|(_, variant)| variant)
+        .collect();
+
+    // solve_for_element2(element1, element3), with the unknown chosen at compile time
+    let mut solve_for = quote! {};
+    for (variable, variant) in variables.iter().zip(&variants) {
+        let function = format_ident!("solve_for_{}", variable.name);
+        let mut parameters = vec![];
+        let mut initializers = vec![];
+        for (name, ty) in &all_fields {
+            match variables.iter().find(|other| &other.name == name) {
+                Some(other) if other.name == variable.name => {
+                    let unknown = other.unknown();
+                    initializers.push(quote! { #name: #unknown });
+                },
+                Some(other) => {
+                    let (value_type, known) = (other.value_type(&scalar), other.known(&quote! { #name }));
+                    parameters.push(quote! { #name: #value_type });
+                    initializers.push(quote! { #name: #known });
+                },
+                None => {
+                    parameters.push(quote! { #name: #ty });
+                    initializers.push(quote! { #name });
+                },
+            }
+        }
+        solve_for = quote! {
+            #solve_for
+
+            #[allow(dead_code)]
+            fn #function(#(#parameters),*) -> Result<#scalar, compute::error::Error> {
+                let equation = Self { #(#initializers),* };
+                equation.solve_for(#var::#variant)?.into_single()
+            }
+        };
+    }
+
     let mut find_unknown = quote! {};
-    for variable in variables {
-        let name = variable.name;
+    let mut write_root = vec![];
+    for (variable, (variant, is_unknown)) in variables.iter().zip(variants.iter().zip(&is_unknown)) {
+        let name = &variable.name;
         let name_s = name.to_string();
+        if let Some(write) = variable.write(&quote! { self.#name }, &quote! { root }) {
+            write_root.push(quote! {
+                if #is_unknown {
+                    #write
+                }
+            });
+        }
         find_unknown = quote! {
             #find_unknown
 
             log_setup(format!("Variable \"{}\" is {:?}", #name_s, self.#name));
 
-            if self.#name.is_unknown() {
+            if #is_unknown {
                 if unknown.is_some() {
                     log_setup(format!("Error: Several unknown"));
                     return Err(Error::SeveralUnknown);
                 }
-                unknown = Some(self.#name.clone());
+                unknown = Some(#var::#variant);
             }
-        }
+        };
     }
 
-    quote! {
-        use compute::error::Error;
+    // Without variables, there may be no named fields to build the struct with
+    let choose_unknown = if variables.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            // The roots of a variable, the other ones being known
+            #[allow(dead_code)]
+            fn solve_for(&self, variable: #var) -> Result<compute::roots::Roots<#scalar>, compute::error::Error> {
+                #(
+                    if variable != #var::#variants && #is_unknown {
+                        return Err(compute::error::Error::SeveralUnknown);
+                    }
+                )*
+                self.relation_for(variable).solve().map(|solution| solution.roots().clone())
+            }
+
+            // Writes the value of the unknown in its field
+            #[allow(dead_code)]
+            fn solve_in_place(&mut self) -> Result<(), compute::error::Error> {
+                let root = self.compute()?.into_single()?;
+                #(#write_root)*
+                Ok(())
+            }
+
+            #solve_for
 
+            // The roots with their derivatives with respect to every known variable
+            #[allow(dead_code)]
+            fn compute_with_sensitivities(
+                &self,
+            ) -> Result<compute::roots::Roots<compute::dual::Sensitivities>, compute::error::Error> {
+                let roots = self.compute()?;
+                let knowns: Vec<&str> = [#((#names_s, #is_unknown)),*]
+                    .into_iter()
+                    .filter_map(|(name, unknown)| Some(name).filter(|_| !unknown))
+                    .collect();
+                let relation = self.differentiated_relation(&knowns);
+                let sensitivities = roots
+                    .iter()
+                    .map(|root| Ok(compute::dual::Sensitivities::new(&relation.root_derivatives(root)?, &knowns)))
+                    .collect::<Result<Vec<_>, compute::error::Error>>()?;
+                Ok(compute::roots::Roots::from(sensitivities))
+            }
+        }
+    };
+
+    // MyEquation::builder().element1(100.).element3(30.).solve(), the variable never set being the unknown
+    let builder = format_ident!("{}Builder", ident);
+    let builder_impl = if variables.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            impl #ident {
+                #[allow(dead_code)]
+                #vis fn builder() -> #builder {
+                    #builder::default()
+                }
+            }
+
+            #[derive(Default)]
+            #vis struct #builder {
+                #(#names: Option<#value_types>,)*
+                #(#others: Option<#other_types>),*
+            }
+
+            #[allow(dead_code)]
+            impl #builder {
+                #(
+                    #vis fn #names(mut self, value: impl Into<#value_types>) -> Self {
+                        self.#names = Some(value.into());
+                        self
+                    }
+                )*
+
+                #(
+                    #vis fn #others(mut self, value: #other_types) -> Self {
+                        self.#others = Some(value);
+                        self
+                    }
+                )*
+
+                // The variable never set, checked to be the only one
+                fn unset_variable(&self) -> Result<#var, compute::error::Error> {
+                    let unset: Vec<_> = [#((#var::#variants, self.#names.is_none())),*]
+                        .into_iter()
+                        .filter_map(|(variable, unset)| Some(variable).filter(|_| unset))
+                        .collect();
+                    match unset.as_slice() {
+                        [] => Err(compute::error::Error::NoUnkown),
+                        [variable] => Ok(*variable),
+                        _ => Err(compute::error::Error::SeveralUnknown),
+                    }
+                }
+
+                // The struct with the values set, a number never set holding a placeholder
+                fn into_equation(self) -> Result<#ident, compute::error::Error> {
+                    Ok(#ident {
+                        #(
+                            #names: match self.#names {
+                                Some(value) => #knowns,
+                                None => #unknowns,
+                            },
+                        )*
+                        #(#others: self.#others.ok_or(compute::error::Error::MissingValue)?),*
+                    })
+                }
+
+                // The equation, checked to have a single unknown before anything is computed
+                #vis fn build(self) -> Result<#ident, compute::error::Error> {
+                    let variable = self.unset_variable()?;
+                    // A number can't hold the unknown, only 'solve' can choose it
+                    if [#(#var::#numbers),*].contains(&variable) {
+                        return Err(compute::error::Error::MissingValue);
+                    }
+                    self.into_equation()
+                }
+
+                #vis fn solve(self) -> Result<compute::roots::Roots<#scalar>, compute::error::Error> {
+                    let variable = self.unset_variable()?;
+                    self.into_equation()?.solve_for(variable)
+                }
+            }
+        }
+    };
+
+    quote! {
         impl #ident {
-            fn compute(&self) -> Result<f64, Error> {
-                use compute::equation::EquationElement;
-                use compute::error::Error;
+            // The relation of 'auto_compute', with the units and names of the fields
+            fn relation(&self) -> compute::relation::Relation<#scalar> {
+                self.equation_relation(None, &[])
+            }
 
+            fn compute(&self) -> Result<compute::roots::Roots<#scalar>, compute::error::Error> {
                 #struct_diagnostics
 
+                self.compute_with_variable().map(|(_, roots)| roots)
+            }
+
+            // The roots with the variable they are the values of
+            fn compute_with_variable(&self) -> Result<(#var, compute::roots::Roots<#scalar>), compute::error::Error> {
+                use compute::equation::EquationElement;
+                use compute::error::Error;
+
                 fn log_setup(message: String) {
                     logger::log(logger::LogStep::Setup, &message);
                 }
@@ -90,13 +491,49 @@ pub fn expand_derive_equation(input: &mut syn::DeriveInput) -> TokenStream {
                         log_setup(format!("Error: No unknown"));
                         Err(Error::NoUnkown)
                     },
-                    Some(unknown) => {
-                        self.auto_compute();
-                        unknown.get_unknown_value()
-                    }
+                    Some(variable) => {
+                        let relation = self.relation();
+                        log_setup(format!("Relation: {}", relation));
+                        relation.solve().map(|solution| (variable, solution.roots().clone()))
+                    },
+                }
+            }
+
+            #[allow(dead_code)]
+            fn variables() -> &'static [#var] {
+                &[#(#var::#variants),*]
+            }
+
+            #equation_relation
+
+            fn relation_for(&self, variable: #var) -> compute::relation::Relation<#scalar> {
+                self.equation_relation(Some(variable), &[])
+            }
+
+            #[allow(dead_code)]
+            fn differentiated_relation(&self, knowns: &[&str]) -> compute::relation::Relation<#scalar> {
+                self.equation_relation(None, knowns)
+            }
+
+            #choose_unknown
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum #var {
+            #(#variants),*
+        }
+
+        impl #var {
+            #[allow(dead_code)]
+            fn name(&self) -> &'static str {
+                match *self {
+                    #(#var::#variants => #names_s),*
                 }
             }
         }
+
+        #builder_impl
+
+        #auto_compute
     }
-    .into()
 }
@@ -1,5 +1,169 @@
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span, TokenStream};
+use syn::spanned::Spanned;
+use syn::{GenericArgument, LitStr, PathArguments, Type};
+
+// The primitive numeric types a variable can be, converted to the scalar without loss to be computed
+const NUMBERS: [&str; 14] = [
+    "f64", "f32", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    // EquationElement<S>, known or unknown
+    Element,
+    // Option<f64>, None being the unknown
+    Optional,
+    // A primitive number, only unknown when chosen to be
+    Number,
+}
+
+impl Kind {
+    // The kind of a variable of type 'ty', None for other types
+    // The types are matched by name, so aliases like 'type Meters = f64' are not supported
+    pub fn of(ty: &Type) -> Option<Kind> {
+        match segment(ty)? {
+            (ident, _) if ident == "EquationElement" => Some(Kind::Element),
+            (ident, Some(_)) if ident == "Option" => Some(Kind::Optional),
+            (ident, None) if NUMBERS.iter().any(|number| ident == number) => Some(Kind::Number),
+            _ => None,
+        }
+    }
+}
+
+// The last segment of a type, with its generic argument
+fn segment(ty: &Type) -> Option<(&Ident, Option<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let argument = match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => arguments.args.iter().find_map(|argument| match argument {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+    Some((&segment.ident, argument))
+}
 
 pub struct Field {
     pub name: Ident,
+    pub ty: Type,
+    pub kind: Kind,
+    pub unit: Option<LitStr>,
+}
+
+impl Field {
+    // The variant of the field in the enum of variables, 'element_2' giving 'Element2'
+    pub fn variant(&self) -> Ident {
+        let variant: String = self
+            .name
+            .to_string()
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+            })
+            .collect();
+        Ident::new(&variant, Span::call_site())
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    fn segment(&self) -> Option<(&Ident, Option<&Type>)> {
+        segment(&self.ty)
+    }
+
+    // The scalar 'S' of an 'EquationElement<S>' field, None when it is left to its default or the field is a number
+    pub fn scalar(&self) -> Option<Type> {
+        match self.kind() {
+            Kind::Element => self.segment()?.1.cloned(),
+            Kind::Optional | Kind::Number => None,
+        }
+    }
+
+    // The type 'T' of an 'Option<T>' field
+    pub fn optional_type(&self) -> Option<&Type> {
+        self.segment().filter(|_| self.kind() == Kind::Optional)?.1
+    }
+
+    // The type of the values given to the field
+    pub fn value_type(&self, scalar: &TokenStream) -> TokenStream {
+        let ty = &self.ty;
+        match self.kind() {
+            Kind::Element => quote! { #scalar },
+            Kind::Optional => quote! { f64 },
+            Kind::Number => quote! { #ty },
+        }
+    }
+
+    // Whether 'value', the field of a struct, is the unknown
+    pub fn is_unknown(&self, value: &TokenStream) -> TokenStream {
+        match self.kind() {
+            Kind::Element => quote! { #value.is_unknown() },
+            Kind::Optional => quote! { #value.is_none() },
+            Kind::Number => quote! { false },
+        }
+    }
+
+    // The field holding a given value
+    pub fn known(&self, value: &TokenStream) -> TokenStream {
+        match self.kind() {
+            Kind::Element => quote! { compute::equation::EquationElement::known(#value) },
+            Kind::Optional => quote! { Some(#value) },
+            Kind::Number => quote! { #value },
+        }
+    }
+
+    // The field holding the unknown, numbers only holding a placeholder
+    pub fn unknown(&self) -> TokenStream {
+        match self.kind() {
+            Kind::Element => quote! { compute::equation::EquationElement::unknown() },
+            Kind::Optional => quote! { None },
+            Kind::Number => quote! { Default::default() },
+        }
+    }
+
+    // The unknown replacing 'value', the field of a struct, an element keeping its name and unit
+    pub fn unknown_element(&self, value: &TokenStream) -> TokenStream {
+        match self.kind() {
+            Kind::Element => quote! { #value.clone().into_unknown() },
+            Kind::Optional | Kind::Number => quote! { compute::equation::EquationElement::unknown() },
+        }
+    }
+
+    // The element of 'value', the field of a struct
+    // A number must convert to the scalar with 'Into', so that a 'u64' is never rounded to an 'f64'
+    pub fn element(&self, value: &TokenStream, scalar: &TokenStream) -> TokenStream {
+        let ty = &self.ty;
+        match self.kind() {
+            Kind::Element => quote! { #value.clone() },
+            Kind::Optional => quote! {
+                match #value {
+                    Some(value) => compute::equation::EquationElement::known(value),
+                    None => compute::equation::EquationElement::unknown(),
+                }
+            },
+            Kind::Number => quote_spanned! {ty.span()=>
+                compute::equation::EquationElement::<#scalar>::known::<#ty>(#value)
+            },
+        }
+    }
+
+    // Writes 'root' in 'value', the field of a struct, None for numbers as they never hold the unknown
+    pub fn write(&self, value: &TokenStream, root: &TokenStream) -> Option<TokenStream> {
+        match self.kind() {
+            Kind::Element => Some(quote! {
+                let mut element = compute::equation::EquationElement::known(#root.clone());
+                if let Some(unit) = #value.unit() {
+                    element.set_unit(unit);
+                }
+                #value = element;
+            }),
+            Kind::Optional => Some(quote! { #value = Some(#root); }),
+            Kind::Number => None,
+        }
+    }
 }
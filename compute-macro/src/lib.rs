@@ -9,9 +9,10 @@ use syn::{parse_macro_input, DeriveInput};
 
 mod equation;
 mod field;
+mod parser;
 mod symbols;
 
-#[proc_macro_derive(Equation, attributes(variable))]
+#[proc_macro_derive(Equation, attributes(variable, equation))]
 pub fn derive_equation(input: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(input as DeriveInput);
     equation::expand_derive_equation(&mut input)
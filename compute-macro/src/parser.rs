@@ -0,0 +1,290 @@
+use std::ops::Range;
+
+use proc_macro2::{Literal, Span, TokenStream};
+use syn::LitStr;
+
+const FUNCTIONS: [&str; 5] = ["sqrt", "exp", "ln", "sin", "cos"];
+
+// A node of the equation written in '#[equation("...")]', with the bytes of the string it comes from
+#[derive(Debug)]
+pub enum Node {
+    Number(f64),
+    Field(String, Range<usize>),
+    Neg(Box<Node>),
+    // '+', '-', '*' or '/'
+    Binary(char, Box<Node>, Box<Node>),
+    Power(Box<Node>, f64),
+    Call(String, Box<Node>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Symbol(char),
+}
+
+struct Parser<'a> {
+    literal: &'a LitStr,
+    tokens: Vec<(Token, Range<usize>)>,
+    position: usize,
+}
+
+// The span of some bytes of the string, if the compiler can point inside literals
+fn subspan(literal: &LitStr, bytes: Range<usize>) -> Option<Span> {
+    let token = literal.token();
+    // The bytes of the value are shifted by the opening quote, unless escapes or a raw string change the layout
+    if token.to_string().len() != literal.value().len() + 2 {
+        return None;
+    }
+    token.subspan(bytes.start + 1..bytes.end + 1)
+}
+
+// The span of some bytes of the string, or of the whole literal
+pub fn span(literal: &LitStr, bytes: Range<usize>) -> Span {
+    subspan(literal, bytes).unwrap_or_else(|| literal.span())
+}
+
+// An error on some bytes of the string, underlined in the message when the span can only cover the whole literal
+pub fn error(literal: &LitStr, bytes: Range<usize>, message: &str) -> syn::Error {
+    if let Some(span) = subspan(literal, bytes.clone()) {
+        return syn::Error::new(span, message);
+    }
+    let value = literal.value();
+    let offset = value[..bytes.start].chars().count();
+    let width = value[bytes].chars().count().max(1);
+    let underline = format!("{}{}", " ".repeat(offset), "^".repeat(width));
+    syn::Error::new(literal.span(), format!("{}\n  {}\n  {}", message, value, underline))
+}
+
+// Parses 'lhs = rhs', the fields being checked later
+pub fn parse(literal: &LitStr) -> Result<(Node, Node), syn::Error> {
+    let mut parser = Parser {
+        literal,
+        tokens: tokenize(literal)?,
+        position: 0,
+    };
+    let lhs = parser.sum()?;
+    parser.expect('=', "Expected '=' between both sides of the equation")?;
+    let rhs = parser.sum()?;
+    if let Some((_, bytes)) = parser.tokens.get(parser.position) {
+        return Err(parser.error(bytes.clone(), "Unexpected token after the equation"));
+    }
+    Ok((lhs, rhs))
+}
+
+fn tokenize(literal: &LitStr) -> Result<Vec<(Token, Range<usize>)>, syn::Error> {
+    let value = literal.value();
+    let chars: Vec<(usize, char)> = value.char_indices().collect();
+    let end_of = |i: usize| chars.get(i).map_or(value.len(), |(byte, _)| *byte);
+    let mut tokens = vec![];
+    let mut i = 0;
+    while let Some(&(start, c)) = chars.get(i) {
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            while chars.get(i).is_some_and(|(_, c)| c.is_ascii_digit() || *c == '.') {
+                i += 1;
+            }
+            // An exponent like '1e-3'
+            if chars.get(i).is_some_and(|(_, c)| *c == 'e' || *c == 'E') {
+                let sign = usize::from(chars.get(i + 1).is_some_and(|(_, c)| *c == '+' || *c == '-'));
+                if chars.get(i + 1 + sign).is_some_and(|(_, c)| c.is_ascii_digit()) {
+                    i += 1 + sign;
+                    while chars.get(i).is_some_and(|(_, c)| c.is_ascii_digit()) {
+                        i += 1;
+                    }
+                }
+            }
+            let bytes = start..end_of(i);
+            let number: f64 = value[bytes.clone()]
+                .parse()
+                .map_err(|_| error(literal, bytes.clone(), "Invalid number"))?;
+            // Like '1e999', which can't be written as a literal
+            if !number.is_finite() {
+                return Err(error(literal, bytes, "Number too large"));
+            }
+            tokens.push((Token::Number(number), bytes));
+        } else if c.is_alphabetic() || c == '_' {
+            while chars.get(i).is_some_and(|(_, c)| c.is_alphanumeric() || *c == '_') {
+                i += 1;
+            }
+            let bytes = start..end_of(i);
+            tokens.push((Token::Identifier(value[bytes.clone()].to_string()), bytes));
+        } else if "+-*/^()=".contains(c) {
+            i += 1;
+            tokens.push((Token::Symbol(c), start..end_of(i)));
+        } else {
+            let bytes = start..end_of(i + 1);
+            return Err(error(literal, bytes, &format!("Unexpected character '{}'", c)));
+        }
+    }
+    Ok(tokens)
+}
+
+impl Parser<'_> {
+    fn error(&self, bytes: Range<usize>, message: &str) -> syn::Error {
+        error(self.literal, bytes, message)
+    }
+
+    // The bytes of the next token, or the end of the string
+    fn next_bytes(&self) -> Range<usize> {
+        match self.tokens.get(self.position) {
+            Some((_, bytes)) => bytes.clone(),
+            None => {
+                let end = self.literal.value().len();
+                end.saturating_sub(1)..end
+            },
+        }
+    }
+
+    fn next_symbol(&self) -> Option<char> {
+        match self.tokens.get(self.position) {
+            Some((Token::Symbol(symbol), _)) => Some(*symbol),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, symbol: char, message: &str) -> Result<(), syn::Error> {
+        if self.next_symbol() != Some(symbol) {
+            return Err(self.error(self.next_bytes(), message));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    // sum = product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Node, syn::Error> {
+        let mut node = self.product()?;
+        while let Some(operator @ ('+' | '-')) = self.next_symbol() {
+            self.position += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.product()?));
+        }
+        Ok(node)
+    }
+
+    // product = unary (('*' | '/') unary)*
+    fn product(&mut self) -> Result<Node, syn::Error> {
+        let mut node = self.unary()?;
+        while let Some(operator @ ('*' | '/')) = self.next_symbol() {
+            self.position += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    // unary = '-' unary | power
+    fn unary(&mut self) -> Result<Node, syn::Error> {
+        if self.next_symbol() == Some('-') {
+            self.position += 1;
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+        self.power()
+    }
+
+    // power = atom ('^' '-'? number)?
+    fn power(&mut self) -> Result<Node, syn::Error> {
+        let base = self.atom()?;
+        if self.next_symbol() != Some('^') {
+            return Ok(base);
+        }
+        self.position += 1;
+        let sign = if self.next_symbol() == Some('-') {
+            self.position += 1;
+            -1.
+        } else {
+            1.
+        };
+        match self.tokens.get(self.position) {
+            Some((Token::Number(exponent), _)) => {
+                self.position += 1;
+                Ok(Node::Power(Box::new(base), sign * exponent))
+            },
+            _ => Err(self.error(self.next_bytes(), "Exponents must be numbers")),
+        }
+    }
+
+    // atom = number | field | function '(' sum ')' | '(' sum ')'
+    fn atom(&mut self) -> Result<Node, syn::Error> {
+        let bytes = self.next_bytes();
+        let Some((token, _)) = self.tokens.get(self.position).cloned() else {
+            return Err(self.error(bytes, "Expected a number, a field or '(' at the end of the equation"));
+        };
+        self.position += 1;
+        match token {
+            Token::Number(number) => Ok(Node::Number(number)),
+            Token::Identifier(name) if self.next_symbol() == Some('(') => {
+                if !FUNCTIONS.contains(&name.as_str()) {
+                    let message = format!("Unknown function '{}', expected one of {}", name, FUNCTIONS.join(", "));
+                    return Err(self.error(bytes, &message));
+                }
+                self.position += 1;
+                let argument = self.sum()?;
+                self.expect(')', "Expected ')' closing the argument")?;
+                Ok(Node::Call(name, Box::new(argument)))
+            },
+            Token::Identifier(name) => Ok(Node::Field(name, bytes)),
+            Token::Symbol('(') => {
+                let node = self.sum()?;
+                self.expect(')', "Expected ')'")?;
+                Ok(node)
+            },
+            Token::Symbol(_) => Err(self.error(bytes, "Expected a number, a field or '('")),
+        }
+    }
+}
+
+impl Node {
+    // The fields written in the equation, with their bytes
+    pub fn fields(&self) -> Vec<(&str, Range<usize>)> {
+        match self {
+            Node::Number(_) => vec![],
+            Node::Field(name, bytes) => vec![(name, bytes.clone())],
+            Node::Neg(operand) | Node::Power(operand, _) | Node::Call(_, operand) => operand.fields(),
+            Node::Binary(_, lhs, rhs) => [lhs.fields(), rhs.fields()].concat(),
+        }
+    }
+
+    // The code building the expression of the node, from a variable of the same name as every field
+    pub fn expression(&self, literal: &LitStr, scalar: &TokenStream) -> TokenStream {
+        let expression = quote! { compute::expression::Expression::<#scalar> };
+        let number = |value: f64| {
+            let value = Literal::f64_suffixed(value);
+            quote! {
+                <#scalar as compute::scalar::Scalar>::from_f64(#value).expect("the numbers of the equation are scalars")
+            }
+        };
+        match self {
+            Node::Number(value) => {
+                let value = number(*value);
+                quote! { #expression::constant(#value) }
+            },
+            Node::Field(name, bytes) => {
+                let field = syn::Ident::new(name, span(literal, bytes.clone()));
+                quote! { #expression::from(#field.clone()) }
+            },
+            Node::Neg(operand) => {
+                let operand = operand.expression(literal, scalar);
+                quote! { -(#operand) }
+            },
+            Node::Binary(operator, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.expression(literal, scalar), rhs.expression(literal, scalar));
+                match operator {
+                    '+' => quote! { (#lhs) + (#rhs) },
+                    '-' => quote! { (#lhs) - (#rhs) },
+                    '*' => quote! { (#lhs) * (#rhs) },
+                    _ => quote! { (#lhs) / (#rhs) },
+                }
+            },
+            Node::Power(base, exponent) => {
+                let (base, exponent) = (base.expression(literal, scalar), number(*exponent));
+                quote! { (#base).powf(#exponent) }
+            },
+            Node::Call(function, argument) => {
+                let argument = argument.expression(literal, scalar);
+                let function = syn::Ident::new(function, Span::call_site());
+                quote! { (#argument).#function() }
+            },
+        }
+    }
+}
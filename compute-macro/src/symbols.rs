@@ -4,6 +4,8 @@ use syn::{Ident, Path};
 pub struct Symbol(&'static str);
 
 pub const VARIABLE: Symbol = Symbol("variable");
+pub const UNIT: Symbol = Symbol("unit");
+pub const EQUATION: Symbol = Symbol("equation");
 
 
 impl PartialEq<Symbol> for Ident {
@@ -12,7 +14,7 @@ impl PartialEq<Symbol> for Ident {
     }
 }
 
-impl<'a> PartialEq<Symbol> for &'a Ident {
+impl PartialEq<Symbol> for &Ident {
     fn eq(&self, word: &Symbol) -> bool {
         *self == word.0
     }
@@ -24,7 +26,7 @@ impl PartialEq<Symbol> for Path {
     }
 }
 
-impl<'a> PartialEq<Symbol> for &'a Path {
+impl PartialEq<Symbol> for &Path {
     fn eq(&self, word: &Symbol) -> bool {
         self.is_ident(word.0)
     }
@@ -0,0 +1,295 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::error::Error;
+use crate::function::Function;
+use crate::roots;
+use crate::scalar::{impl_scalar_lhs_ops, Scalar};
+use crate::tolerance::Tolerance;
+
+const TOLERANCE: f64 = 1e-12;
+
+// A complex number 're + im·i', for impedances or the roots of polynomials without real ones
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    pub const I: Complex = Complex { re: 0., im: 1. };
+
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    pub fn from_polar(norm: f64, arg: f64) -> Self {
+        Self::new(norm * arg.cos(), norm * arg.sin())
+    }
+
+    pub fn re(&self) -> f64 {
+        self.re
+    }
+
+    pub fn im(&self) -> f64 {
+        self.im
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    // The angle with the positive real axis, in '(-pi, pi]'
+    pub fn arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(&self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.im == 0.
+    }
+
+    // The principal square root, with a positive real part
+    pub fn sqrt(&self) -> Self {
+        let norm = self.norm();
+        let re = ((norm + self.re) / 2.).sqrt();
+        let im = ((norm - self.re) / 2.).sqrt();
+        Self::new(re, if self.im < 0. { -im } else { im })
+    }
+
+    pub fn exp(&self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    // The principal logarithm, None for 0
+    pub fn ln(&self) -> Option<Self> {
+        Some(Self::new(self.norm().ln(), self.arg())).filter(|_| *self != Self::from(0.))
+    }
+
+    pub fn sin(&self) -> Self {
+        Self::new(self.re.sin() * self.im.cosh(), self.re.cos() * self.im.sinh())
+    }
+
+    pub fn cos(&self) -> Self {
+        Self::new(self.re.cos() * self.im.cosh(), -self.re.sin() * self.im.sinh())
+    }
+
+    // 'self^exponent = exp(exponent * ln(self))', with '0^exponent = 0' for a positive real part
+    pub fn powc(&self, exponent: Complex) -> Option<Self> {
+        match self.ln() {
+            Some(ln) => Some((exponent * ln).exp()),
+            None => Some(Self::from(0.)).filter(|_| exponent.re > 0.),
+        }
+    }
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Self {
+        Self::new(re, 0.)
+    }
+}
+
+// Written like '3+4i', in one piece so it reads as a single coefficient in a formula
+impl Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.im == 0. {
+            write!(f, "{}", self.re)
+        } else if self.re == 0. {
+            write!(f, "{}i", self.im)
+        } else if self.im < 0. {
+            write!(f, "{}-{}i", self.re, -self.im)
+        } else {
+            write!(f, "{}+{}i", self.re, self.im)
+        }
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Neg for Complex {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let norm = rhs.re * rhs.re + rhs.im * rhs.im;
+        Self::new(
+            (self.re * rhs.re + self.im * rhs.im) / norm,
+            (self.im * rhs.re - self.re * rhs.im) / norm,
+        )
+    }
+}
+
+impl Scalar for Complex {
+    fn zero() -> Self {
+        Self::from(0.)
+    }
+
+    fn one() -> Self {
+        Self::from(1.)
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.norm()
+    }
+
+    fn approx_eq(&self, other: &Self, scale: f64) -> bool {
+        (*self - *other).norm() <= TOLERANCE * scale.max(self.norm()).max(other.norm())
+    }
+
+    fn default_tolerance() -> Tolerance {
+        Tolerance::relative(TOLERANCE)
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        match self.re.is_finite() && self.im.is_finite() {
+            true => Ok(()),
+            false => Err(Error::NotRepresentable),
+        }
+    }
+
+    // Only real numbers have a value as f64
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.re).filter(|_| self.is_real())
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(Self::from(value))
+    }
+
+    fn apply(&self, function: &Function<Self>) -> Option<Self> {
+        match function {
+            Function::Sqrt => Some(self.sqrt()),
+            Function::Exp => Some(self.exp()),
+            Function::Ln => self.ln(),
+            Function::Sin => Some(self.sin()),
+            Function::Cos => Some(self.cos()),
+            Function::Powf(exponent) => self.powc(*exponent),
+        }
+    }
+
+    fn polynomial_roots(coefficients: &[Self]) -> Result<Vec<Self>, Error> {
+        Ok(roots::complex_roots(coefficients))
+    }
+
+    // Every root is already a complex number
+    fn complex_roots(_coefficients: &[Self]) -> Vec<Complex> {
+        vec![]
+    }
+}
+
+impl_scalar_lhs_ops!(Complex);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::element::Element;
+    use crate::equation::EquationElement;
+    use crate::roots::Roots;
+
+    fn assert_close(value: Complex, expected: Complex) {
+        assert!((value - expected).norm() < 1e-9, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn test_complex_arithmetic() {
+        let z = Complex::new(3., 4.);
+        assert_eq!(z.norm(), 5.);
+        assert_eq!(z * z.conj(), Complex::from(25.));
+        assert_eq!(z / Complex::I, Complex::new(4., -3.));
+        assert_eq!(Complex::I * Complex::I, Complex::from(-1.));
+        assert_close(Complex::from(-4.).sqrt(), Complex::new(0., 2.));
+        assert_close(z.sqrt() * z.sqrt(), z);
+        assert_close(Complex::new(0., std::f64::consts::PI).exp(), Complex::from(-1.));
+        assert_close(z.ln().unwrap().exp(), z);
+        assert_eq!(Complex::from(0.).ln(), None);
+        assert_close(z.sin() * z.sin() + z.cos() * z.cos(), Complex::from(1.));
+
+        assert_eq!(z.to_string(), "3+4i");
+        assert_eq!(z.conj().to_string(), "3-4i");
+        assert_eq!(Complex::I.to_string(), "1i");
+        assert_eq!(Complex::from(-2.).to_string(), "-2");
+        assert_eq!(z.to_f64(), None);
+        assert_eq!(Complex::from(2.).to_f64(), Some(2.));
+    }
+
+    #[test]
+    fn test_complex_roots() {
+        let rc = Rc::new(RefCell::new(Complex::zero()));
+        let x = || Element::new_unknown(rc.clone());
+
+        // x^2 + 4 = 0
+        let roots = (x() * x() + Complex::from(4.)).equals(Complex::zero()).solve().unwrap();
+        assert_eq!(roots.roots(), &Roots::from(vec![Complex::new(0., -2.), Complex::new(0., 2.)]));
+
+        // x^3 = 1, the cube roots of unity
+        let solution = (x() * x() * x()).equals(Complex::one()).solve().unwrap();
+        assert_eq!(solution.roots().len(), 3);
+        solution.roots().iter().for_each(|root| assert_close(*root * *root * *root, Complex::one()));
+
+        // An impedance in series with a capacitor, for a total of '5+5i'
+        let total = EquationElement::known(Complex::new(5., 5.));
+        let capacitor = EquationElement::known(Complex::new(0., -2.));
+        let z = EquationElement::<Complex>::unknown();
+        let solution = (z.clone() + capacitor).equals(total).solve().unwrap();
+        assert_eq!(solution.roots(), &Roots::from(Complex::new(5., 7.)));
+    }
+
+    #[test]
+    fn test_complex_roots_of_real_equations() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = || Element::new_unknown(rc.clone());
+
+        // x^2 + 2x + 5 = 0 has no real root, the complex ones are reported instead
+        let solution = (x() * x() + x() * 2. + 5.).equals(0.).solve().unwrap();
+        assert!(solution.roots().is_empty());
+        assert_eq!(solution.complex_roots(), &[Complex::new(-1., -2.), Complex::new(-1., 2.)]);
+
+        // (x - 1)(x^2 + 1) = 0
+        let solution = ((x() - 1.) * (x() * x() + 1.)).equals(0.).solve().unwrap();
+        assert_eq!(solution.roots(), &Roots::from(1.));
+        assert_eq!(solution.complex_roots().len(), 2);
+        solution.complex_roots().iter().for_each(|root| assert_close(*root * *root, Complex::from(-1.)));
+
+        let solution = (x() * x()).equals(4.).solve().unwrap();
+        assert!(solution.complex_roots().is_empty());
+    }
+}
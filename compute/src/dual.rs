@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::function::Function;
+use crate::scalar::{impl_scalar_lhs_ops, Scalar};
+use crate::tolerance::Tolerance;
+
+// A value with its derivatives with respect to several variables, for forward mode automatic differentiation
+// Missing derivatives are null, so constants have none
+#[derive(Clone, Debug)]
+pub struct Dual {
+    value: f64,
+    derivatives: Vec<f64>,
+}
+
+impl Dual {
+    pub fn constant(value: f64) -> Self {
+        Self {
+            value,
+            derivatives: vec![],
+        }
+    }
+
+    // The variable whose derivatives are tracked at 'index'
+    pub fn variable(value: f64, index: usize) -> Self {
+        let mut derivatives = vec![0.; index + 1];
+        derivatives[index] = 1.;
+        Self { value, derivatives }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn derivative(&self, index: usize) -> f64 {
+        self.derivatives.get(index).copied().unwrap_or(0.)
+    }
+
+    pub fn derivatives(&self) -> &[f64] {
+        &self.derivatives
+    }
+
+    // 'f(self)', knowing 'f'(self)'
+    fn chain(&self, value: f64, derivative: f64) -> Self {
+        Self {
+            value,
+            derivatives: self.derivatives.iter().map(|d| d * derivative).collect(),
+        }
+    }
+
+    fn combine<F>(&self, rhs: &Self, combinator: F) -> Vec<f64>
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let len = self.derivatives.len().max(rhs.derivatives.len());
+        (0..len).map(|i| combinator(self.derivative(i), rhs.derivative(i))).collect()
+    }
+}
+
+impl From<f64> for Dual {
+    fn from(value: f64) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl PartialEq for Dual {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.value == rhs.value && self.combine(rhs, |d1, d2| d1 - d2).iter().all(|d| *d == 0.)
+    }
+}
+
+impl PartialOrd for Dual {
+    fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&rhs.value)
+    }
+}
+
+// Only the value is written
+impl Display for Dual {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Add for Dual {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            value: self.value + rhs.value,
+            derivatives: self.combine(&rhs, |d1, d2| d1 + d2),
+        }
+    }
+}
+
+impl Neg for Dual {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.chain(-self.value, -1.)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            value: self.value - rhs.value,
+            derivatives: self.combine(&rhs, |d1, d2| d1 - d2),
+        }
+    }
+}
+
+impl Mul for Dual {
+    type Output = Self;
+
+    // The product rule adds the derivatives
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            value: self.value * rhs.value,
+            derivatives: self.combine(&rhs, |d1, d2| d1 * rhs.value + self.value * d2),
+        }
+    }
+}
+
+impl Div for Dual {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let square = rhs.value * rhs.value;
+        Self {
+            value: self.value / rhs.value,
+            derivatives: self.combine(&rhs, |d1, d2| (d1 * rhs.value - self.value * d2) / square),
+        }
+    }
+}
+
+impl Scalar for Dual {
+    fn zero() -> Self {
+        Self::constant(0.)
+    }
+
+    fn one() -> Self {
+        Self::constant(1.)
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.value.abs()
+    }
+
+    fn approx_eq(&self, other: &Self, scale: f64) -> bool {
+        self.value.approx_eq(&other.value, scale)
+    }
+
+    fn default_tolerance() -> Tolerance {
+        f64::default_tolerance()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.value)
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(Self::constant(value))
+    }
+
+    // The derivatives follow the chain rule
+    fn apply(&self, function: &Function<Self>) -> Option<Self> {
+        let x = self.value;
+        let value = function.evaluate_f64(x)?;
+        let result = match function {
+            Function::Sqrt => self.chain(value, 0.5 / value),
+            Function::Exp => self.chain(value, value),
+            Function::Ln => self.chain(value, 1. / x),
+            Function::Sin => self.chain(value, x.cos()),
+            Function::Cos => self.chain(value, -x.sin()),
+            // d(x^p) = p * x^(p - 1) * dx + x^p * ln(x) * dp
+            Function::Powf(exponent) => {
+                let p = exponent.value;
+                let base = self.chain(value, p * x.powf(p - 1.));
+                let exponent = exponent.chain(0., if value == 0. { 0. } else { value * x.ln() });
+                base + exponent
+            },
+        };
+        Some(result)
+    }
+
+    // With 'f(root, others) = 0', 'd(root) = -(df/d(others)) / (df/d(root))'
+    fn implicit_root<F>(root: Self, f: F) -> Self
+    where
+        F: Fn(&Self) -> Option<Self>,
+    {
+        let Some(residual) = f(&Self::constant(root.value)) else {
+            return root;
+        };
+        let index = residual.derivatives.len();
+        let Some(slope) = f(&Self::variable(root.value, index)).map(|value| value.derivative(index)) else {
+            return root;
+        };
+        if slope == 0. || !slope.is_finite() {
+            return root;
+        }
+        Self {
+            value: root.value,
+            derivatives: residual.derivatives.iter().map(|d| -d / slope).collect(),
+        }
+    }
+}
+
+impl_scalar_lhs_ops!(Dual);
+
+// A root of an equation, with its derivative with respect to each known field
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sensitivities {
+    value: f64,
+    derivatives: BTreeMap<String, f64>,
+}
+
+impl Sensitivities {
+    // 'fields' are the fields differentiated, in the order of the derivatives of 'root'
+    pub fn new(root: &Dual, fields: &[&str]) -> Self {
+        Self {
+            value: root.value,
+            derivatives: fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| (field.to_string(), root.derivative(index)))
+                .collect(),
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    // d(unknown) / d(field), None if the field is not a known
+    pub fn derivative(&self, field: &str) -> Option<f64> {
+        self.derivatives.get(field).copied()
+    }
+
+    pub fn derivatives(&self) -> &BTreeMap<String, f64> {
+        &self.derivatives
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::element::Element;
+    use crate::relation::Method;
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn test_dual_arithmetic() {
+        let x = Dual::variable(3., 0);
+        let y = Dual::variable(2., 1);
+
+        let product = x.clone() * y.clone();
+        assert_eq!((product.value(), product.derivatives()), (6., &[2., 3.][..]));
+        let quotient = x.clone() / y.clone();
+        assert_eq!((quotient.value(), quotient.derivatives()), (1.5, &[0.5, -0.75][..]));
+        assert_eq!(x.clone() - x.clone(), Dual::zero());
+        let sum = x.clone() + Dual::from(1.);
+        assert_eq!((sum.derivative(0), sum.derivative(1)), (1., 0.));
+
+        let exp = x.apply(&Function::Exp).unwrap();
+        assert_close(exp.derivative(0), 3f64.exp());
+        let power = x.apply(&Function::Powf(y)).unwrap();
+        assert_close(power.derivative(0), 6.);
+        assert_close(power.derivative(1), 9. * 3f64.ln());
+        assert_eq!(Dual::constant(-1.).apply(&Function::Ln), None);
+    }
+
+    #[test]
+    fn test_root_derivatives() {
+        let rc = Rc::new(RefCell::new(Dual::zero()));
+        let x = || Element::new_unknown(rc.clone());
+        let a = Dual::variable(2., 0);
+        let b = Dual::variable(8., 1);
+
+        // a * x = b, x = b / a
+        let root = (x() * a.clone()).equals(b.clone()).solve().unwrap().roots().single().unwrap().clone();
+        assert_close(root.value(), 4.);
+        assert_close(root.derivative(0), -2.);
+        assert_close(root.derivative(1), 0.5);
+
+        // a * x^2 = b, x = ±sqrt(b / a)
+        let solution = (x() * x() * a.clone()).equals(b.clone()).solve().unwrap();
+        let root = solution.roots().iter().find(|root| root.value() > 0.).unwrap();
+        assert_close(root.value(), 2.);
+        assert_close(root.derivative(0), -0.5);
+        assert_close(root.derivative(1), 0.125);
+
+        // exp(a * x) = b, x = ln(b) / a
+        let solution = (x() * a.clone()).exp().equals(b.clone()).solve().unwrap();
+        assert_eq!(solution.method(), Method::Analytic);
+        let root = solution.roots().single().unwrap();
+        assert_close(root.derivative(0), -8f64.ln() / 4.);
+        assert_close(root.derivative(1), 1. / 16.);
+
+        // x + sin(x) = b, numerically
+        let solution = (x() + x().sin()).equals(b).solve().unwrap();
+        assert_eq!(solution.method(), Method::Brent);
+        let root = solution.roots().single().unwrap();
+        assert_close(root.derivative(1), 1. / (1. + root.value().cos()));
+    }
+}
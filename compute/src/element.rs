@@ -1,326 +1,595 @@
 use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
 use std::ops::{Add, Div, Mul, Neg, Sub};
 use std::rc::Rc;
 
 use crate::error::Error;
-use crate::unknown::Unknown;
-
-type Ax = (f64, Unknown);
-
-// Represents an element like 'a * x + b'
+use crate::format::{Formula, Names, Notation};
+use crate::function::{Atom, Function};
+use crate::inequality::{Comparison, Inequality};
+use crate::polynomial::Polynomial;
+use crate::relation::Relation;
+use crate::scalar::Scalar;
+use crate::tolerance::Tolerance;
+use crate::unit::{Dimension, Unit};
+use crate::unknown::{Unknown, UnknownId};
+
+pub const DEFAULT_MAX_DEGREE: u32 = 16;
+
+type Unknowns<S> = BTreeMap<UnknownId, Unknown<S>>;
+type Atoms<S> = BTreeMap<UnknownId, Rc<Atom<S>>>;
+type Fraction<S> = (Polynomial<S>, Polynomial<S>);
+
+// Represents a rational element like '(a * x^2 + b * x * y + c) / (d * x + e)'
+// Functions like 'exp(x)' are atoms, seen by the polynomials as unknowns of their own
 #[derive(Clone, Debug)]
-pub struct Element {
-    ax: Option<(f64, Unknown)>,
-    b: f64,
+pub struct Element<S = f64> {
+    unknowns: Unknowns<S>,
+    atoms: Atoms<S>,
+    // Names of the unknowns, used to write the element
+    names: BTreeMap<UnknownId, String>,
+    numerator: Polynomial<S>,
+    denominator: Polynomial<S>,
+    max_degree: u32,
+    // None for elements of any dimension, like the unknowns and knowns created without unit
+    dimension: Option<Dimension>,
     error: Result<(), Error>,
 }
 
-impl Element {
-    pub fn new_unknown(x: Rc<RefCell<f64>>) -> Self {
+impl<S: Scalar> Element<S> {
+    pub fn new_unknown(x: Rc<RefCell<S>>) -> Self {
+        Self::from_unknown(Unknown::new_with_value(x))
+    }
+
+    pub(crate) fn from_unknown(x: Unknown<S>) -> Self {
         Self {
-            ax: Some((1., Unknown::new_with_value(x))),
-            b: 0.,
+            numerator: Polynomial::unknown(x.id()),
+            denominator: Polynomial::constant(S::one()),
+            unknowns: BTreeMap::from([(x.id(), x)]),
+            atoms: BTreeMap::new(),
+            names: BTreeMap::new(),
+            max_degree: DEFAULT_MAX_DEGREE,
+            dimension: None,
             error: Ok(()),
         }
     }
 
-    pub fn new_known(known: f64) -> Self {
+    pub fn new_known(known: S) -> Self {
         Self {
-            ax: None,
-            b: known,
-            error: Ok(()),
+            unknowns: BTreeMap::new(),
+            atoms: BTreeMap::new(),
+            names: BTreeMap::new(),
+            error: known.check(),
+            numerator: Polynomial::constant(known),
+            denominator: Polynomial::constant(S::one()),
+            max_degree: DEFAULT_MAX_DEGREE,
+            dimension: None,
         }
     }
-}
 
-fn combine<F>(first: Option<Ax>, second: Option<Ax>, combinator: F) -> Option<Ax>
-    where F: Fn(f64, f64) -> Result<f64, Error> {
-        match (first, second) {
-            (Some(first), Some(second)) => {
-                let mut x = Unknown {
-                    status: first.1.status.and_then(|_| second.1.status),
-                    unknown: first.1.unknown,
-                };
+    // The highest degree a product can reach before failing with 'Error::DegreeTooHigh'
+    pub fn with_max_degree(self, max_degree: u32) -> Self {
+        Self { max_degree, ..self }
+    }
 
-                if x.status.is_ok() {
-                    let combined = combinator(first.0, second.0);
-                    x.status = x.status.and(combined.clone().map(|_| ()));
+    pub fn with_dimension(self, dimension: Dimension) -> Self {
+        Self {
+            dimension: Some(dimension),
+            ..self
+        }
+    }
 
-                    Some((combined.unwrap_or(first.0), x))
-                } else {
-                    Some((first.0, x))
-                }
+    pub fn dimension(&self) -> Option<Dimension> {
+        self.dimension
+    }
+
+    // Converts an element whose values are expressed in 'unit' to SI units
+    pub fn in_unit(self, unit: Unit) -> Self {
+        match unit.scalar_factor::<S>() {
+            Ok(factor) => (self * factor).with_dimension(unit.dimension()),
+            Err(error) => Self {
+                error: self.error.and(Err(error)),
+                ..self
             },
-            (Some(first), None) => Some(first),
-            (None, Some(second)) => Some(second),
-            (None, None) => None,
         }
-}
+    }
 
-impl Add for Element {
-    type Output = Self;
+    pub(crate) fn with_unknown_name(mut self, id: UnknownId, name: String) -> Self {
+        self.names.insert(id, name);
+        self
+    }
 
-    fn add(self, rhs: Self) -> Self {
-        let error = self.error.and(rhs.error);
+    pub(crate) fn names(&self) -> &BTreeMap<UnknownId, String> {
+        &self.names
+    }
 
-        let mut ax = combine(self.ax, rhs.ax, |ax1, ax2| Ok(ax1 + ax2));
-        ax.as_mut().map(|ax| ax.1.status.clone().and(error.clone()));
+    pub(crate) fn unknowns(&self) -> impl Iterator<Item = &Unknown<S>> {
+        self.unknowns.values()
+    }
 
-        Self {
-            ax,
-            b: self.b + rhs.b,
-            error,
+    // The unknowns the value of the element depends on, including the ones inside functions
+    pub(crate) fn unknown_ids(&self) -> BTreeSet<UnknownId> {
+        self.numerator
+            .unknowns()
+            .union(&self.denominator.unknowns())
+            .flat_map(|id| match self.atoms.get(id) {
+                Some(atom) => atom.argument.unknown_ids(),
+                None => BTreeSet::from([*id]),
+            })
+            .collect()
+    }
+
+    pub(crate) fn atom(&self, id: UnknownId) -> Option<&Atom<S>> {
+        self.atoms.get(&id).map(|atom| atom.as_ref())
+    }
+
+    // Whether the element is a rational function of its unknowns, without any function
+    pub(crate) fn is_algebraic(&self) -> bool {
+        self.numerator
+            .unknowns()
+            .union(&self.denominator.unknowns())
+            .all(|id| !self.atoms.contains_key(id))
+    }
+
+    // The values of the unknowns completed by the values of the atoms
+    pub(crate) fn variable_values(&self, values: &BTreeMap<UnknownId, S>) -> Option<BTreeMap<UnknownId, S>> {
+        let mut variables = values.clone();
+        for (id, atom) in &self.atoms {
+            variables.insert(*id, atom.evaluate(values)?);
         }
+        Some(variables)
     }
-}
 
-impl Add<f64> for Element {
-    type Output = Self;
+    // None if a function is evaluated out of its domain
+    pub(crate) fn evaluate(&self, values: &BTreeMap<UnknownId, S>) -> Option<S> {
+        self.evaluate_as(values, &S::clone)
+    }
 
-    fn add(self, rhs: f64) -> Self {
-        Self {
-            ax: self.ax,
-            b: self.b + rhs,
-            error: self.error,
+    // The value computed with another scalar, like 'Dual' to get the derivatives, the coefficients being converted by
+    // 'convert'
+    pub(crate) fn evaluate_as<T, F>(&self, values: &BTreeMap<UnknownId, T>, convert: &F) -> Option<T>
+    where
+        T: Scalar,
+        F: Fn(&S) -> T,
+    {
+        let mut variables = values.clone();
+        for (id, atom) in &self.atoms {
+            variables.insert(*id, atom.evaluate_as(values, convert)?);
         }
+        Some(self.numerator.evaluate_as(&variables, convert) / self.denominator.evaluate_as(&variables, convert))
     }
-}
 
-impl Add<Element> for f64 {
-    type Output = Element;
+    pub fn sqrt(self) -> Self {
+        // The exponents of the dimension are halved
+        let dimension = self.dimension.map(|dimension| {
+            let exponents = dimension.exponents();
+            if exponents.iter().all(|exponent| exponent % 2 == 0) {
+                Ok(Dimension::new(exponents.map(|exponent| exponent / 2)))
+            } else {
+                Err(Error::DimensionMismatch)
+            }
+        });
+        self.apply(Function::Sqrt, dimension.transpose())
+    }
 
-    fn add(self, rhs: Element) -> Self::Output {
-        rhs + self
+    pub fn exp(self) -> Self {
+        let dimension = self.dimensionless_argument();
+        self.apply(Function::Exp, dimension)
     }
-}
 
-impl Neg for Element {
-    type Output = Self;
+    pub fn ln(self) -> Self {
+        let dimension = self.dimensionless_argument();
+        self.apply(Function::Ln, dimension)
+    }
 
-    fn neg(self) -> Self {
-        Self {
-            ax: self.ax.map(|ax| (-ax.0, ax.1)),
-            b: -self.b,
-            error: self.error,
+    pub fn sin(self) -> Self {
+        let dimension = self.dimensionless_argument();
+        self.apply(Function::Sin, dimension)
+    }
+
+    pub fn cos(self) -> Self {
+        let dimension = self.dimensionless_argument();
+        self.apply(Function::Cos, dimension)
+    }
+
+    // Integer exponents stay polynomial, other ones need 'x >= 0'
+    pub fn powf(self, exponent: S) -> Self {
+        let integer = exponent
+            .to_f64()
+            .filter(|exponent| exponent.fract() == 0. && exponent.abs() <= self.max_degree as f64);
+        if let Some(integer) = integer {
+            let one = match self.dimension {
+                Some(_) => Element::new_known(S::one()).with_dimension(Dimension::NONE),
+                None => Element::new_known(S::one()),
+            };
+            let power = (0..integer.abs() as u32).fold(one.clone(), |power, _| power * self.clone());
+            return if integer < 0. { one / power } else { power };
         }
+
+        let dimension = match self.dimension {
+            Some(dimension) if dimension != Dimension::NONE => Err(Error::DimensionMismatch),
+            dimension => Ok(dimension),
+        };
+        self.apply(Function::Powf(exponent), dimension)
     }
-}
 
-impl Sub for Element {
-    type Output = Self;
+    pub fn call(self, function: Function<S>) -> Self {
+        match function {
+            Function::Sqrt => self.sqrt(),
+            Function::Exp => self.exp(),
+            Function::Ln => self.ln(),
+            Function::Sin => self.sin(),
+            Function::Cos => self.cos(),
+            Function::Powf(exponent) => self.powf(exponent),
+        }
+    }
 
-    fn sub(self, rhs: Self) -> Self {
-        let error = self.error.and(rhs.error);
+    // 'known / rhs', where the scalar has no dimension
+    pub(crate) fn known_over(known: S, rhs: Self) -> Self {
+        let known = Element::new_known(known);
+        match rhs.dimension {
+            Some(_) => known.with_dimension(Dimension::NONE) / rhs,
+            None => known / rhs,
+        }
+    }
 
-        let mut ax = combine(self.ax.clone(), rhs.ax, |ax1, ax2| Ok(ax1 - ax2));
-        ax.as_mut().map(|ax| ax.1.status.clone().and(error.clone()));
-        if self.ax.is_none() {
-            if let Some(ax) = ax.as_mut() {
-                ax.0 = -ax.0;
-            }
+    fn dimensionless_argument(&self) -> Result<Option<Dimension>, Error> {
+        match self.dimension {
+            Some(dimension) if dimension != Dimension::NONE => Err(Error::DimensionMismatch),
+            dimension => Ok(dimension),
         }
+    }
 
+    // A constant is evaluated right away, anything else becomes an atom
+    fn apply(self, function: Function<S>, dimension: Result<Option<Dimension>, Error>) -> Self {
+        let error = self.error.clone().and(dimension.clone().map(|_| ()));
+        let dimension = dimension.unwrap_or(None);
+        if self.numerator.is_constant() && self.denominator.is_constant() {
+            let value = self.numerator.constant_term() / self.denominator.constant_term();
+            let value = value.apply(&function);
+            return Self {
+                error: error.and(value.as_ref().map(|_| ()).ok_or(Error::OutOfDomain)),
+                dimension,
+                ..Element::new_known(value.unwrap_or_else(S::zero))
+            };
+        }
+
+        let unknowns = self.unknowns.clone();
+        let names = self.names.clone();
+        let max_degree = self.max_degree;
+        let atom = Rc::new(Atom {
+            function,
+            argument: self,
+        });
+        let id = Rc::as_ptr(&atom) as UnknownId;
         Self {
-            ax,
-            b: self.b - rhs.b,
+            unknowns,
+            atoms: BTreeMap::from([(id, atom)]),
+            names,
+            numerator: Polynomial::unknown(id),
+            denominator: Polynomial::constant(S::one()),
+            max_degree,
+            dimension,
             error,
         }
     }
-}
 
-impl Sub<f64> for Element {
-    type Output = Self;
+    pub(crate) fn numerator(&self) -> &Polynomial<S> {
+        &self.numerator
+    }
 
-    fn sub(self, rhs: f64) -> Self {
+    pub(crate) fn denominator(&self) -> &Polynomial<S> {
+        &self.denominator
+    }
+
+    // The element without the terms of its numerator that are negligible compared to 'scale'
+    pub(crate) fn truncated(self, tolerance: &Tolerance, scale: f64) -> Self {
         Self {
-            ax: self.ax,
-            b: self.b - rhs,
-            error: self.error,
+            numerator: self.numerator.truncated(|a| tolerance.is_negligible(a.magnitude(), scale)),
+            ..self
         }
     }
-}
 
-impl Sub<Element> for f64 {
-    type Output = Element;
+    pub(crate) fn error(&self) -> Result<(), Error> {
+        self.error.clone()
+    }
 
-    fn sub(self, rhs: Element) -> Self::Output {
-        -rhs + self
+    pub fn equals<R>(self, rhs: R) -> Relation<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Relation::new(self, rhs)
     }
-}
 
-impl Mul for Element {
-    type Output = Self;
+    pub fn lt<R>(self, rhs: R) -> Inequality<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Inequality::new(self, Comparison::Less, rhs)
+    }
 
-    fn mul(self, rhs: Self) -> Self {
-        let error = self.error.and(rhs.error);
+    pub fn le<R>(self, rhs: R) -> Inequality<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Inequality::new(self, Comparison::LessOrEqual, rhs)
+    }
+
+    pub fn gt<R>(self, rhs: R) -> Inequality<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Inequality::new(self, Comparison::Greater, rhs)
+    }
+
+    pub fn ge<R>(self, rhs: R) -> Inequality<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Inequality::new(self, Comparison::GreaterOrEqual, rhs)
+    }
+
+    pub fn to_latex(&self) -> String {
+        self.formula(&Names::of(&[self])).write(Notation::Latex)
+    }
+
+    pub fn to_mathml(&self) -> String {
+        self.formula(&Names::of(&[self])).write(Notation::MathMl)
+    }
 
-        let mut ax = combine(self.ax.clone(), rhs.ax, |_, _| Err(Error::SquareForbidden));
-        if let Some(ax) = ax.as_mut() {
-            ax.0 *= if self.ax.is_some() { rhs.b } else { self.b };
-            ax.1.status = ax.1.status.clone().and(error.clone());
+    pub(crate) fn formula(&self, names: &Names) -> Formula {
+        let variable = |id| match self.atoms.get(&id) {
+            Some(atom) => Formula::call(&atom.function, atom.argument.formula(names)),
+            None => names.variable(id),
+        };
+        Formula::fraction(
+            Formula::polynomial(&self.numerator, variable),
+            Formula::polynomial(&self.denominator, variable),
+        )
+    }
+
+    fn combine<F>(self, rhs: Self, dimension: Result<Option<Dimension>, Error>, combinator: F) -> Self
+    where
+        F: FnOnce(Fraction<S>, Fraction<S>) -> Result<Fraction<S>, Error>,
+    {
+        let max_degree = self.max_degree.min(rhs.max_degree);
+        let first = (self.numerator, self.denominator);
+        // A constant denominator is folded into the numerator, so that linear elements stay polynomials
+        // It is kept when the division isn't exact, like '3 / 2' with integers
+        let combined = combinator(first.clone(), (rhs.numerator, rhs.denominator))
+            .map(|(numerator, denominator)| {
+                let quotient = Some(&denominator)
+                    .filter(|denominator| denominator.is_constant())
+                    .map(|denominator| numerator.clone() / denominator.constant_term())
+                    .filter(|quotient| quotient.check().is_ok());
+                match quotient {
+                    Some(quotient) => (quotient, Polynomial::constant(S::one())),
+                    None => (numerator, denominator),
+                }
+            })
+            .and_then(|(numerator, denominator)| {
+                numerator.check()?;
+                denominator.check()?;
+                if numerator.degree().max(denominator.degree()) > max_degree {
+                    Err(Error::DegreeTooHigh)
+                } else {
+                    Ok((numerator, denominator))
+                }
+            });
+
+        let error = self
+            .error
+            .and(rhs.error)
+            .and(dimension.clone().map(|_| ()))
+            .and(combined.clone().map(|_| ()));
+        let mut atoms = self.atoms;
+        atoms.extend(rhs.atoms);
+        let mut names = self.names;
+        names.extend(rhs.names);
+        let mut unknowns = self.unknowns;
+        for (id, x2) in rhs.unknowns {
+            let x1 = unknowns.entry(id).or_insert_with(|| x2.clone());
+            x1.status = x1.status.clone().and(x2.status);
         }
+        for x in unknowns.values_mut() {
+            x.status = x.status.clone().and(error.clone());
+        }
+
+        let (numerator, denominator) = combined.unwrap_or(first);
 
         Self {
-            ax,
-            b: self.b * rhs.b,
+            unknowns,
+            atoms,
+            names,
+            numerator,
+            denominator,
+            max_degree,
+            dimension: dimension.unwrap_or(None),
             error,
         }
     }
 }
 
-impl Mul<f64> for Element {
+impl<S: Scalar> From<S> for Element<S> {
+    fn from(known: S) -> Self {
+        Element::new_known(known)
+    }
+}
+
+// Only elements of the same dimension can be added or compared
+fn same_dimension(first: Option<Dimension>, second: Option<Dimension>) -> Result<Option<Dimension>, Error> {
+    match (first, second) {
+        (Some(first), Some(second)) if first != second => Err(Error::DimensionMismatch),
+        (Some(dimension), _) | (_, Some(dimension)) => Ok(Some(dimension)),
+        (None, None) => Ok(None),
+    }
+}
+
+fn add<S: Scalar>((n1, d1): Fraction<S>, (n2, d2): Fraction<S>) -> Result<Fraction<S>, Error> {
+    if d1 == d2 {
+        Ok((n1 + n2, d1))
+    } else {
+        Ok((n1 * d2.clone() + n2 * d1.clone(), d1 * d2))
+    }
+}
+
+impl<S: Scalar> Add for Element<S> {
     type Output = Self;
 
-    fn mul(self, rhs: f64) -> Self {
-        Self {
-            ax: self.ax.map(|ax| (ax.0 * rhs, ax.1)),
-            b: self.b * rhs,
-            error: self.error,
-        }
+    fn add(self, rhs: Self) -> Self {
+        let dimension = same_dimension(self.dimension, rhs.dimension);
+        self.combine(rhs, dimension, add)
     }
 }
 
-impl Mul<Element> for f64 {
-    type Output = Element;
+impl<S: Scalar> Add<S> for Element<S> {
+    type Output = Self;
 
-    fn mul(self, rhs: Element) -> Self::Output {
-        rhs * self
+    fn add(self, rhs: S) -> Self {
+        self + Element::new_known(rhs)
     }
 }
 
-fn combine_div(first: Option<Ax>, second: Option<Ax>) -> Option<Ax> {
-    match (first, second) {
-        (Some(first), Some(second)) => {
-            let x = Unknown {
-                status: first.1.status.and(second.1.status).and(Err(Error::UnknownInDenominator)),
-                unknown: first.1.unknown,
-            };
+impl<S: Scalar> Neg for Element<S> {
+    type Output = Self;
 
-            Some((second.0, x))
-        },
-        (Some(first), None) => Some(first),
-        (None, Some(second)) => {
-            let x = Unknown {
-                status: second.1.status.and(Err(Error::UnknownInDenominator)),
-                unknown: second.1.unknown,
-            };
+    fn neg(self) -> Self {
+        Self {
+            numerator: -self.numerator,
+            ..self
+        }
+    }
+}
 
-            Some((second.0, x))
-        },
-        (None, None) => None,
+impl<S: Scalar> Sub for Element<S> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let dimension = same_dimension(self.dimension, rhs.dimension);
+        self.combine(rhs, dimension, |(n1, d1), (n2, d2)| add((n1, d1), (-n2, d2)))
     }
 }
 
-impl Div for Element {
+impl<S: Scalar> Sub<S> for Element<S> {
     type Output = Self;
 
-    fn div(self, rhs: Element) -> Self {
-        let error = self.error
-            .and(rhs.error)
-            .and_then(|_| {
-                if rhs.ax.is_some() {
-                    Err(Error::UnknownInDenominator)
-                } else if rhs.b == 0. {
-                    Err(Error::DivisionByZero)
-                } else {
-                    Ok(())
-                }
-            });
-        let mut ax = combine_div(self.ax, rhs.ax);
-        if let Some(ax) = ax.as_mut() {
-            ax.0 /= rhs.b;
-            ax.1.status = ax.1.status.clone().and(error.clone());
-        }
+    fn sub(self, rhs: S) -> Self {
+        self - Element::new_known(rhs)
+    }
+}
 
-        Self {
-            ax,
-            b: if error.is_ok() {
-                self.b / rhs.b
-            } else {
-                self.b
-            },
-            error,
-        }
+impl<S: Scalar> Mul for Element<S> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let dimension = Ok(self.dimension.zip(rhs.dimension).map(|(d1, d2)| d1 * d2));
+        self.combine(rhs, dimension, |(n1, d1), (n2, d2)| Ok((n1 * n2, d1 * d2)))
     }
 }
 
-impl Div<f64> for Element {
+impl<S: Scalar> Mul<S> for Element<S> {
     type Output = Self;
 
-    fn div(self, rhs: f64) -> Self {
+    // A scalar has no dimension, so it scales the element without changing its dimension
+    fn mul(self, rhs: S) -> Self {
+        let dimension = self.dimension;
         Self {
-            ax: self.ax.map(|ax| (ax.0 / rhs, ax.1)),
-            b: self.b / rhs,
-            error: self.error,
+            dimension,
+            ..self * Element::new_known(rhs)
         }
     }
 }
 
-impl Div<Element> for f64 {
-    type Output = Element;
+impl<S: Scalar> Div for Element<S> {
+    type Output = Self;
 
-    fn div(self, rhs: Element) -> Self::Output {
-        Element::new_known(self) / rhs
+    fn div(self, rhs: Self) -> Self {
+        let dimension = Ok(self.dimension.zip(rhs.dimension).map(|(d1, d2)| d1 / d2));
+        self.combine(rhs, dimension, |(n1, d1), (n2, d2)| {
+            if n2 == Polynomial::default() {
+                Err(Error::DivisionByZero)
+            } else {
+                Ok((n1 * d2, d1 * n2))
+            }
+        })
     }
 }
 
-impl PartialEq for Element {
-    fn eq(&self, rhs: &Self) -> bool {
-        let lhs = self.clone() - rhs.clone();
-        if let Some(ax) = lhs.ax {
-            if ax.0 != 0. && ax.1.status.is_ok() {
-                *ax.1.unknown.borrow_mut() = -lhs.b / ax.0;
-            }
+impl<S: Scalar> Div<S> for Element<S> {
+    type Output = Self;
+
+    fn div(self, rhs: S) -> Self {
+        let dimension = self.dimension;
+        Self {
+            dimension,
+            ..self / Element::new_known(rhs)
         }
+    }
+}
 
-        let ax_eq = match (self.ax.clone(), rhs.ax.clone()) {
-            (Some(ax1), Some(ax2)) => ax1.0 == ax2.0 && ax1.1.status == ax1.1.status,
-            (None, None) => true,
-            _ => false,
-        };
-        ax_eq && self.b == rhs.b
+impl<S: Scalar> Display for Element<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.formula(&Names::of(&[self])).write(Notation::Text))
     }
 }
 
-impl PartialEq<f64> for Element {
-    fn eq(&self, rhs: &f64) -> bool {
-        self == &Element::new_known(*rhs)
+impl<S: Scalar> PartialEq for Element<S> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.numerator == rhs.numerator
+            && self.denominator == rhs.denominator
+            && self.dimension == rhs.dimension
+            && self.error == rhs.error
     }
 }
 
-impl PartialEq<Element> for f64 {
-    fn eq(&self, rhs: &Element) -> bool {
-        &Element::new_known(*self) == rhs
+impl<S: Scalar> PartialEq<S> for Element<S> {
+    fn eq(&self, rhs: &S) -> bool {
+        self == &Element::new_known(rhs.clone())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::relation::Solution;
 
     impl Element {
         fn new(ax: Option<(f64, Rc<RefCell<f64>>)>, b: f64) -> Self {
-            Self {
-                ax: ax.map(|ax| (ax.0, Unknown::new_with_value(ax.1))),
-                b,
-                error: Ok(()),
-            }
+            Self::new_several(ax.into_iter().collect(), b)
+        }
+
+        fn new_several(ax: Vec<(f64, Rc<RefCell<f64>>)>, b: f64) -> Self {
+            ax.into_iter()
+                .fold(Element::new_known(b), |sum, (a, x)| sum + a * Element::new_unknown(x))
+        }
+
+        // a * x^2 + b * x + c
+        fn new_quadratic(a: f64, b: f64, c: f64, x: Rc<RefCell<f64>>) -> Self {
+            let element = Element::new_unknown(x);
+            a * element.clone() * element.clone() + b * element + c
         }
     }
 
     struct Setup {
         rc: Rc<RefCell<f64>>,
+        other_rc: Rc<RefCell<f64>>,
     }
 
     impl Setup {
         fn new() -> Self {
             Self {
                 rc: Rc::new(RefCell::new(0.)),
+                other_rc: Rc::new(RefCell::new(0.)),
             }
         }
 
-        fn check_rc(&mut self, value: f64) {
-            assert_eq!(*self.rc.borrow(), value);
+        fn check_roots(solution: Result<Solution, Error>, roots: &[f64]) {
+            let found = solution.unwrap().roots().clone();
+            assert_eq!(found.len(), roots.len(), "{:?} != {:?}", found, roots);
+            for (found, root) in found.iter().zip(roots) {
+                assert!((found - root).abs() < 1e-12, "{:?} != {:?}", found, root);
+            }
         }
     }
 
@@ -421,10 +690,16 @@ mod tests {
     fn test_mul_elements() {
         let setup = Setup::new();
 
-        // (x + 3) * (2x + 4) => error
+        // (x + 3) * (2x + 4) = 2x^2 + 10x + 12
         let element1 = Element::new(Some((1., setup.rc.clone())), 3.);
         let element2 = Element::new(Some((2., setup.rc.clone())), 4.);
-        assert_eq!((element1 * element2).ax.unwrap().1.status, Err(Error::SquareForbidden));
+        let mul = Element::new_quadratic(2., 10., 12., setup.rc.clone());
+        assert_eq!(element1 * element2, mul);
+
+        // (x^2) * (x) with a maximum degree of 2 => error
+        let element1 = Element::new_quadratic(1., 0., 0., setup.rc.clone()).with_max_degree(2);
+        let element2 = Element::new(Some((1., setup.rc.clone())), 0.);
+        assert_eq!((element1 * element2).error, Err(Error::DegreeTooHigh));
 
         // (3) * (2x + 4) = 6x + 12
         let element1 = Element::new(None, 3.);
@@ -449,13 +724,12 @@ mod tests {
     fn test_div_elements() {
         let setup = Setup::new();
 
-        // (x + 3) / (2x + 4) => error
+        // (x + 3) / (2x + 4) = (x + 3) / (2x + 4)
         let element1 = Element::new(Some((1., setup.rc.clone())), 3.);
         let element2 = Element::new(Some((2., setup.rc.clone())), 4.);
-        assert_eq!(
-            (element1 / element2).ax.unwrap().1.status,
-            Err(Error::UnknownInDenominator)
-        );
+        let div = element1.clone() / element2.clone();
+        assert_eq!(div.numerator, element1.numerator);
+        assert_eq!(div.denominator, element2.numerator);
 
         // (x + 3) / (4) = x/4 + 3/4
         let element1 = Element::new(Some((1., setup.rc.clone())), 3.);
@@ -466,7 +740,7 @@ mod tests {
         // (x + 3) / (0) => error
         let element1 = Element::new(Some((1., setup.rc.clone())), 3.);
         let element2 = Element::new(None, 0.);
-        assert_eq!((element1 / element2).ax.unwrap().1.status, Err(Error::DivisionByZero));
+        assert_eq!((element1 / element2).error, Err(Error::DivisionByZero));
 
         // (3) / (4) = x/4 + 3/4
         let element1 = Element::new(None, 3.);
@@ -477,30 +751,117 @@ mod tests {
 
     #[test]
     fn test_solve_elements() {
-        let mut setup = Setup::new();
+        let setup = Setup::new();
 
         // x + 1 = 0
         let element1 = Element::new(Some((1., setup.rc.clone())), 1.);
         let element2 = Element::new(None, 0.);
-        let _ = element1 == element2;
-        setup.check_rc(-1.);
+        Setup::check_roots(element1.equals(element2).solve(), &[-1.]);
 
         // 2x + 1 = 0
         let element1 = Element::new(Some((2., setup.rc.clone())), 1.);
         let element2 = Element::new(None, 0.);
-        let _ = element1 == element2;
-        setup.check_rc(-0.5);
+        Setup::check_roots(element1.equals(element2).solve(), &[-0.5]);
 
         // 2x + 1 = x
         let element1 = Element::new(Some((2., setup.rc.clone())), 1.);
         let element2 = Element::new(Some((1., setup.rc.clone())), 0.);
-        let _ = element1 == element2;
-        setup.check_rc(-1.);
+        Setup::check_roots(element1.equals(element2).solve(), &[-1.]);
 
         // 4x + 1 = 2x + 3
         let element1 = Element::new(Some((4., setup.rc.clone())), 1.);
         let element2 = Element::new(Some((2., setup.rc.clone())), 3.);
-        let _ = element1 == element2;
-        setup.check_rc(1.);
+        Setup::check_roots(element1.equals(element2).solve(), &[1.]);
+    }
+
+    #[test]
+    fn test_several_unknowns_elements() {
+        let setup = Setup::new();
+
+        // (x + 3) + (y + 4) = x + y + 7
+        let element1 = Element::new(Some((1., setup.rc.clone())), 3.);
+        let element2 = Element::new(Some((1., setup.other_rc.clone())), 4.);
+        let sum = Element::new_several(vec![(1., setup.rc.clone()), (1., setup.other_rc.clone())], 7.);
+        assert_eq!(element1 + element2, sum);
+
+        // (2x + y) - (x + 3y) = x - 2y
+        let element1 = Element::new_several(vec![(2., setup.rc.clone()), (1., setup.other_rc.clone())], 0.);
+        let element2 = Element::new_several(vec![(1., setup.rc.clone()), (3., setup.other_rc.clone())], 0.);
+        let sub = Element::new_several(vec![(1., setup.rc.clone()), (-2., setup.other_rc.clone())], 0.);
+        assert_eq!(element1 - element2, sub);
+
+        // x + y != 2x
+        let element1 = Element::new_several(vec![(1., setup.rc.clone()), (1., setup.other_rc.clone())], 0.);
+        let element2 = Element::new(Some((2., setup.rc.clone())), 0.);
+        assert_ne!(element1, element2);
+
+        // x + y = 1 can't be solved
+        let element1 = Element::new_several(vec![(1., setup.rc.clone()), (1., setup.other_rc.clone())], 0.);
+        let element2 = Element::new(None, 1.);
+        assert_eq!(element1.equals(element2).solve(), Err(Error::SeveralUnknown));
+
+        // x + y = y + 3
+        let element1 = Element::new_several(vec![(1., setup.rc.clone()), (1., setup.other_rc.clone())], 0.);
+        let element2 = Element::new(Some((1., setup.other_rc.clone())), 3.);
+        let solution = element1.equals(element2).solve();
+        assert_eq!(solution.clone().unwrap().unknown().id(), Unknown::new_with_value(setup.rc.clone()).id());
+        Setup::check_roots(solution, &[3.]);
+    }
+
+    #[test]
+    fn test_solve_polynomial_elements() {
+        let setup = Setup::new();
+
+        // x^2 = 4
+        let element1 = Element::new_quadratic(1., 0., 0., setup.rc.clone());
+        let element2 = Element::new(None, 4.);
+        Setup::check_roots(element1.equals(element2).solve(), &[-2., 2.]);
+
+        // x^2 + 2x = -1
+        let element1 = Element::new_quadratic(1., 2., 0., setup.rc.clone());
+        let element2 = Element::new(None, -1.);
+        Setup::check_roots(element1.equals(element2).solve(), &[-1.]);
+
+        // x^2 = -1
+        let element1 = Element::new_quadratic(1., 0., 0., setup.rc.clone());
+        let element2 = Element::new(None, -1.);
+        Setup::check_roots(element1.equals(element2).solve(), &[]);
+
+        // x^3 = 8x
+        let x = Element::new(Some((1., setup.rc.clone())), 0.);
+        let element1 = x.clone() * x.clone() * x.clone();
+        let element2 = 8. * x;
+        Setup::check_roots(element1.equals(element2).solve(), &[-(8_f64.sqrt()), 0., 8_f64.sqrt()]);
+    }
+
+    #[test]
+    fn test_solve_rational_elements() {
+        let setup = Setup::new();
+
+        // 10 / x = 2
+        let element1 = 10. / Element::new(Some((1., setup.rc.clone())), 0.);
+        let element2 = Element::new(None, 2.);
+        Setup::check_roots(element1.equals(element2).solve(), &[5.]);
+
+        // (x + 1) / (x - 1) = 3
+        let element1 = Element::new(Some((1., setup.rc.clone())), 1.) / Element::new(Some((1., setup.rc.clone())), -1.);
+        let element2 = Element::new(None, 3.);
+        Setup::check_roots(element1.equals(element2).solve(), &[2.]);
+
+        // (x^2 - 1) / (x - 1) = 0, x = 1 cancels the denominator
+        let element1 = Element::new_quadratic(1., 0., -1., setup.rc.clone()) / Element::new(Some((1., setup.rc.clone())), -1.);
+        let element2 = Element::new(None, 0.);
+        Setup::check_roots(element1.equals(element2).solve(), &[-1.]);
+
+        // 1 / x + 1 / (x - 1) = 0
+        let element1 = 1. / Element::new(Some((1., setup.rc.clone())), 0.)
+            + 1. / Element::new(Some((1., setup.rc.clone())), -1.);
+        let element2 = Element::new(None, 0.);
+        Setup::check_roots(element1.equals(element2).solve(), &[0.5]);
+
+        // x / x = 2, x = 0 cancels the denominator
+        let element1 = Element::new(Some((1., setup.rc.clone())), 0.) / Element::new(Some((1., setup.rc.clone())), 0.);
+        let element2 = Element::new(None, 2.);
+        Setup::check_roots(element1.equals(element2).solve(), &[]);
     }
 }
@@ -1,36 +1,121 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
 use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::rc::Rc;
 
-use crate::{element::Element, error::Error, unknown::Unknown};
+use crate::format::{Formula, Names, Notation};
+use crate::inequality::{Comparison, Inequality};
+use crate::{element::Element, error::Error, relation::Relation, scalar::Scalar, unit::Unit};
+use crate::unknown::{Unknown, UnknownId};
 
-pub trait EquationAutoCompute {
-    fn auto_compute(&self) -> bool;
+pub trait EquationAutoCompute<S = f64> {
+    fn auto_compute(&self) -> Relation<S>;
 }
 
 // TODO I don't like the whole EquationElement -> TODOElement & EquationElement redeclaring all the Element functions
 #[derive(Clone, Debug)]
-pub enum TODOElement {
-    Known(f64),
-    Unknown(Unknown),
+pub enum TODOElement<S = f64> {
+    Known(S),
+    Unknown(Unknown<S>),
 }
 
 #[derive(Clone, Debug)]
-pub struct EquationElement {
-    todo_element: TODOElement,
+pub struct EquationElement<S = f64> {
+    todo_element: TODOElement<S>,
+    // The unit of the value, or of the solution for an unknown
+    unit: Option<Unit>,
+    // The name used in expressions
+    name: Option<String>,
+    // Shared by the clones of the element, so that a 'Lowering' recognizes the field they come from
+    field: Rc<()>,
 }
 
-impl EquationElement {
-    pub fn known<T>(value: T) -> EquationElement
+// The copies given for the fields by a lowering, as 'EquationElement<S>' for any scalar
+type Copies = Vec<(Rc<()>, Rc<dyn Any>)>;
+
+thread_local! {
+    // The lowerings of the relations being written, the innermost last
+    static LOWERINGS: RefCell<Vec<Copies>> = const { RefCell::new(vec![]) };
+}
+
+// The copies the fields of a struct are lowered as while 'auto_compute' writes its relation, like the unknown
+// replacing the variable solved for, so that the fields themselves are never changed
+// Set by the derive macro, as 'auto_compute' builds its relation from the fields of '&self'
+pub struct Lowering<S = f64> {
+    fields: Vec<(Rc<()>, EquationElement<S>)>,
+}
+
+impl<S: Scalar> EquationElement<S> {
+    pub fn known<T>(value: T) -> EquationElement<S>
     where
-        T: Into<f64>
+        T: Into<S>
     {
         EquationElement {
             todo_element: TODOElement::Known(value.into()),
+            unit: None,
+            name: None,
+            field: Rc::new(()),
         }
     }
 
-    pub fn unknown() -> EquationElement {
+    pub fn unknown() -> EquationElement<S> {
+        Self::from_unknown(Unknown::new())
+    }
+
+    pub(crate) fn from_unknown(unknown: Unknown<S>) -> EquationElement<S> {
+        EquationElement {
+            todo_element: TODOElement::Unknown(unknown),
+            unit: None,
+            name: None,
+            field: Rc::new(()),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> EquationElement<S> {
+        self.set_name(name);
+        self
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = Some(name.to_string());
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn with_unit(mut self, unit: &str) -> Result<EquationElement<S>, Error> {
+        self.set_unit(Unit::parse(unit)?);
+        Ok(self)
+    }
+
+    pub fn set_unit(&mut self, unit: Unit) {
+        self.unit = Some(unit);
+    }
+
+    pub fn unit(&self) -> Option<Unit> {
+        self.unit
+    }
+
+    // A known lowered as an unknown holding its value, so that the derivatives with respect to it are
+    // the ones of index 'index', used by 'compute_with_sensitivities()'
+    pub fn differentiated(self, index: usize) -> EquationElement<S> {
+        match self.todo_element {
+            TODOElement::Known(value) => EquationElement {
+                todo_element: TODOElement::Unknown(Unknown::parameter(value, index)),
+                ..self
+            },
+            TODOElement::Unknown(_) => self,
+        }
+    }
+
+    // A new unknown replacing the element, with its name and unit
+    pub fn into_unknown(self) -> EquationElement<S> {
         EquationElement {
             todo_element: TODOElement::Unknown(Unknown::new()),
+            ..self
         }
     }
 
@@ -38,218 +123,341 @@ impl EquationElement {
         matches!(self.todo_element, TODOElement::Unknown(_))
     }
 
-    pub fn get_unknown_value(&self) -> Result<f64, Error> {
-        if let TODOElement::Unknown(unknown) = &self.todo_element {
-            unknown.clone().status.and(Ok(unknown.unknown.borrow().clone()))
-        } else {
-            panic!("TODO look into this, can it happen?");
+    // The value written by the last solve, a known having none
+    pub fn get_unknown_value(&self) -> Result<S, Error> {
+        match &self.todo_element {
+            TODOElement::Unknown(unknown) => unknown.clone().status.and(Ok(unknown.unknown.borrow().clone())),
+            TODOElement::Known(_) => Err(Error::NoUnkown),
         }
     }
+
+    pub fn sqrt(self) -> Element<S> {
+        Element::from(self).sqrt()
+    }
+
+    pub fn exp(self) -> Element<S> {
+        Element::from(self).exp()
+    }
+
+    pub fn ln(self) -> Element<S> {
+        Element::from(self).ln()
+    }
+
+    pub fn sin(self) -> Element<S> {
+        Element::from(self).sin()
+    }
+
+    pub fn cos(self) -> Element<S> {
+        Element::from(self).cos()
+    }
+
+    pub fn powf(self, exponent: S) -> Element<S> {
+        Element::from(self).powf(exponent)
+    }
+
+    pub fn equals<R>(self, rhs: R) -> Relation<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Relation::new(self, rhs)
+    }
+
+    pub fn lt<R>(self, rhs: R) -> Inequality<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Inequality::new(self, Comparison::Less, rhs)
+    }
+
+    pub fn le<R>(self, rhs: R) -> Inequality<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Inequality::new(self, Comparison::LessOrEqual, rhs)
+    }
+
+    pub fn gt<R>(self, rhs: R) -> Inequality<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Inequality::new(self, Comparison::Greater, rhs)
+    }
+
+    pub fn ge<R>(self, rhs: R) -> Inequality<S>
+    where
+        R: Into<Element<S>>,
+    {
+        Inequality::new(self, Comparison::GreaterOrEqual, rhs)
+    }
+
+    pub fn to_latex(&self) -> String {
+        self.formula(&self.names()).write(Notation::Latex)
+    }
+
+    pub fn to_mathml(&self) -> String {
+        self.formula(&self.names()).write(Notation::MathMl)
+    }
+
+    pub(crate) fn unknown_id(&self) -> Option<UnknownId> {
+        match &self.todo_element {
+            TODOElement::Unknown(unknown) => Some(unknown.id()),
+            TODOElement::Known(_) => None,
+        }
+    }
+
+    // Written with its name, or its value if it is a known without name
+    pub(crate) fn formula(&self, names: &Names) -> Formula {
+        match (self.name(), &self.todo_element) {
+            (Some(name), _) => Formula::Variable(name, None),
+            (None, TODOElement::Known(value)) => Formula::number(value),
+            (None, TODOElement::Unknown(unknown)) => names.variable(unknown.id()),
+        }
+    }
+
+    fn names(&self) -> Names {
+        Names::new(BTreeMap::new(), BTreeSet::from_iter(self.unknown_id()))
+    }
+
+    // The copy given for the field the element was cloned from, by the innermost lowering giving one
+    pub(crate) fn lowered(self) -> Self {
+        let copy = LOWERINGS.with(|lowerings| {
+            lowerings
+                .borrow()
+                .iter()
+                .rev()
+                .flatten()
+                .find(|(field, _)| Rc::ptr_eq(field, &self.field))
+                .and_then(|(_, copy)| copy.downcast_ref::<Self>().cloned())
+        });
+        copy.unwrap_or(self)
+    }
 }
 
-impl From<EquationElement> for Element {
-    fn from(e: EquationElement) -> Self {
-        match e.todo_element {
-            TODOElement::Known(b) => Element::new_known(b),
-            TODOElement::Unknown(x) => Element::new_unknown(x.unknown),
+impl<S: Scalar> Lowering<S> {
+    pub fn new() -> Self {
+        Self { fields: vec![] }
+    }
+
+    // The elements cloned from 'field' are lowered as 'copy'
+    pub fn with_field(mut self, field: &EquationElement<S>, copy: EquationElement<S>) -> Self {
+        // The copy is not a field anymore, even if cloned from one
+        let copy = EquationElement {
+            field: Rc::new(()),
+            ..copy
+        };
+        self.fields.push((field.field.clone(), copy));
+        self
+    }
+
+    // The value of 'write', the fields being lowered as their copies until it returns or panics
+    pub fn apply<R, F>(self, write: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        struct Applied;
+        impl Drop for Applied {
+            fn drop(&mut self) {
+                LOWERINGS.with(|lowerings| lowerings.borrow_mut().pop());
+            }
         }
+
+        let fields = self.fields.into_iter().map(|(field, copy)| (field, Rc::new(copy) as Rc<dyn Any>));
+        LOWERINGS.with(|lowerings| lowerings.borrow_mut().push(fields.collect()));
+        let _applied = Applied;
+        write()
     }
 }
 
-impl Add for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Default for Lowering<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Element::from(self) + Element::from(rhs)
+impl<S: Scalar> Display for EquationElement<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.formula(&self.names()).write(Notation::Text))
     }
 }
 
-impl Add<f64> for EquationElement {
-    type Output = Element;
+impl<S: Scalar> From<EquationElement<S>> for Element<S> {
+    fn from(e: EquationElement<S>) -> Self {
+        let e = e.lowered();
+        let name = e.name();
+        let element = match e.todo_element {
+            TODOElement::Known(b) => Element::new_known(b),
+            TODOElement::Unknown(x) => match name {
+                Some(name) => Element::from_unknown(x.clone()).with_unknown_name(x.id(), name),
+                None => Element::from_unknown(x),
+            },
+        };
+        match e.unit {
+            Some(unit) => element.in_unit(unit),
+            None => element,
+        }
+    }
+}
 
-    fn add(self, rhs: f64) -> Self::Output {
-        Element::from(self) + rhs
+impl<S: Scalar> Add for EquationElement<S> {
+    type Output = Element<S>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Element::from(self) + Element::from(rhs)
     }
 }
 
-impl Add<EquationElement> for f64 {
-    type Output = Element;
+impl<S: Scalar> Add<S> for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn add(self, rhs: EquationElement) -> Self::Output {
-        Element::from(rhs) + self
+    fn add(self, rhs: S) -> Self::Output {
+        Element::from(self) + rhs
     }
 }
 
-impl Add<Element> for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Add<Element<S>> for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn add(self, rhs: Element) -> Self::Output {
+    fn add(self, rhs: Element<S>) -> Self::Output {
         Element::from(self) + rhs
     }
 }
 
-impl Add<EquationElement> for Element {
-    type Output = Element;
+impl<S: Scalar> Add<EquationElement<S>> for Element<S> {
+    type Output = Element<S>;
 
-    fn add(self, rhs: EquationElement) -> Self::Output {
+    fn add(self, rhs: EquationElement<S>) -> Self::Output {
         Element::from(rhs) + self
     }
 }
 
-impl Neg for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Neg for EquationElement<S> {
+    type Output = Element<S>;
 
     fn neg(self) -> Self::Output {
         -Element::from(self)
     }
 }
 
-impl Sub for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Sub for EquationElement<S> {
+    type Output = Element<S>;
 
     fn sub(self, rhs: Self) -> Self::Output {
         Element::from(self) - Element::from(rhs)
     }
 }
 
-impl Sub<f64> for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Sub<S> for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn sub(self, rhs: f64) -> Self::Output {
+    fn sub(self, rhs: S) -> Self::Output {
         Element::from(self) - rhs
     }
 }
 
-impl Sub<EquationElement> for f64 {
-    type Output = Element;
+impl<S: Scalar> Sub<Element<S>> for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn sub(self, rhs: EquationElement) -> Self::Output {
-        -Element::from(rhs) + self
-    }
-}
-
-impl Sub<Element> for EquationElement {
-    type Output = Element;
-
-    fn sub(self, rhs: Element) -> Self::Output {
+    fn sub(self, rhs: Element<S>) -> Self::Output {
         Element::from(self) - rhs
     }
 }
 
-impl Sub<EquationElement> for Element {
-    type Output = Element;
+impl<S: Scalar> Sub<EquationElement<S>> for Element<S> {
+    type Output = Element<S>;
 
-    fn sub(self, rhs: EquationElement) -> Self::Output {
+    fn sub(self, rhs: EquationElement<S>) -> Self::Output {
         -Element::from(rhs) + self
     }
 }
 
-impl Mul for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Mul for EquationElement<S> {
+    type Output = Element<S>;
 
     fn mul(self, rhs: Self) -> Self::Output {
         Element::from(self) * Element::from(rhs)
     }
 }
 
-impl Mul<f64> for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Mul<S> for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn mul(self, rhs: f64) -> Self::Output {
+    fn mul(self, rhs: S) -> Self::Output {
         Element::from(self) * rhs
     }
 }
 
-impl Mul<EquationElement> for f64 {
-    type Output = Element;
-
-    fn mul(self, rhs: EquationElement) -> Self::Output {
-        Element::from(rhs) * self
-    }
-}
-
-impl Mul<Element> for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Mul<Element<S>> for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn mul(self, rhs: Element) -> Self::Output {
+    fn mul(self, rhs: Element<S>) -> Self::Output {
         Element::from(self) * rhs
     }
 }
 
-impl Mul<EquationElement> for Element {
-    type Output = Element;
+impl<S: Scalar> Mul<EquationElement<S>> for Element<S> {
+    type Output = Element<S>;
 
-    fn mul(self, rhs: EquationElement) -> Self::Output {
+    fn mul(self, rhs: EquationElement<S>) -> Self::Output {
         Element::from(rhs) * self
     }
 }
 
-impl Div for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Div for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn div(self, rhs: EquationElement) -> Self::Output {
+    fn div(self, rhs: EquationElement<S>) -> Self::Output {
         Element::from(self) / Element::from(rhs)
     }
 }
 
-impl Div<f64> for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Div<S> for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn div(self, rhs: f64) -> Self::Output {
+    fn div(self, rhs: S) -> Self::Output {
         Element::from(self) / rhs
     }
 }
 
-impl Div<EquationElement> for f64 {
-    type Output = Element;
-
-    fn div(self, rhs: EquationElement) -> Self::Output {
-        self / Element::from(rhs)
-    }
-}
-
-impl Div<Element> for EquationElement {
-    type Output = Element;
+impl<S: Scalar> Div<Element<S>> for EquationElement<S> {
+    type Output = Element<S>;
 
-    fn div(self, rhs: Element) -> Self::Output {
+    fn div(self, rhs: Element<S>) -> Self::Output {
         Element::from(self) / rhs
     }
 }
 
-impl Div<EquationElement> for Element {
-    type Output = Element;
+impl<S: Scalar> Div<EquationElement<S>> for Element<S> {
+    type Output = Element<S>;
 
-    fn div(self, rhs: EquationElement) -> Self::Output {
+    fn div(self, rhs: EquationElement<S>) -> Self::Output {
         self / Element::from(rhs)
     }
 }
 
-impl PartialEq for EquationElement {
-    fn eq(&self, rhs: &EquationElement) -> bool {
-        Element::from(self.clone()) == Element::from(rhs.clone())
-    }
-}
-
-impl PartialEq<f64> for EquationElement {
-    fn eq(&self, rhs: &f64) -> bool {
-        &Element::from(self.clone()) == rhs
+impl<S: Scalar> PartialEq for EquationElement<S> {
+    fn eq(&self, rhs: &EquationElement<S>) -> bool {
+        match (&self.todo_element, &rhs.todo_element) {
+            (TODOElement::Known(known1), TODOElement::Known(known2)) => known1 == known2,
+            (TODOElement::Unknown(x1), TODOElement::Unknown(x2)) => x1.id() == x2.id(),
+            _ => false,
+        }
     }
 }
 
-impl PartialEq<EquationElement> for f64 {
-    fn eq(&self, rhs: &EquationElement) -> bool {
-        self == &Element::from(rhs.clone())
+impl<S: Scalar> PartialEq<S> for EquationElement<S> {
+    fn eq(&self, rhs: &S) -> bool {
+        matches!(&self.todo_element, TODOElement::Known(known) if known == rhs)
     }
 }
 
-impl PartialEq<Element> for EquationElement {
-    fn eq(&self, rhs: &Element) -> bool {
+impl<S: Scalar> PartialEq<Element<S>> for EquationElement<S> {
+    fn eq(&self, rhs: &Element<S>) -> bool {
         &Element::from(self.clone()) == rhs
     }
 }
 
-impl PartialEq<EquationElement> for Element {
-    fn eq(&self, rhs: &EquationElement) -> bool {
+impl<S: Scalar> PartialEq<EquationElement<S>> for Element<S> {
+    fn eq(&self, rhs: &EquationElement<S>) -> bool {
         self == &Element::from(rhs.clone())
     }
 }
@@ -3,6 +3,22 @@ pub enum Error {
     NoUnkown,
     SeveralUnknown,
     DivisionByZero,
-    SquareForbidden,
-    UnknownInDenominator,
+    DegreeTooHigh,
+    SingularSystem,
+    InconsistentSystem,
+    NonLinear,
+    Overflow,
+    NotRepresentable,
+    DimensionMismatch,
+    InvalidUnit,
+    OutOfDomain,
+    NoConvergence,
+    // The unknown cancels out of a relation that never holds, like 'x + 1 = x + 2'
+    NoSolution,
+    // The unknown cancels out of a relation that always holds, like 'x + 1 = x + 1'
+    InfiniteSolutions,
+    // A single value is asked for, but the relation has several roots, like 'x^2 = 4'
+    SeveralRoots,
+    // A field that can't be the unknown was never given a value
+    MissingValue,
 }
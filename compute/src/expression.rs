@@ -0,0 +1,315 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::rc::Rc;
+
+use crate::element::Element;
+use crate::equation::EquationElement;
+use crate::format::{Formula, Names, Notation};
+use crate::function::Function;
+use crate::relation::Relation;
+use crate::scalar::Scalar;
+use crate::unknown::{Unknown, UnknownId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOperator {
+    Neg,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+// The tree of the operations written, unlike an element that only keeps the resulting polynomials
+// It is lowered to an element to be solved
+#[derive(Clone, Debug)]
+pub enum Expression<S = f64> {
+    Constant(S),
+    // A known or unknown element, named after its field in an equation
+    Variable(EquationElement<S>),
+    Unary(UnaryOperator, Box<Expression<S>>),
+    Binary(BinaryOperator, Box<Expression<S>>, Box<Expression<S>>),
+    Function(Function<S>, Box<Expression<S>>),
+}
+
+impl<S: Scalar> Expression<S> {
+    pub fn constant(value: S) -> Self {
+        Expression::Constant(value)
+    }
+
+    pub fn unknown(name: &str, x: Rc<RefCell<S>>) -> Self {
+        Expression::Variable(EquationElement::from_unknown(Unknown::new_with_value(x)).with_name(name))
+    }
+
+    pub fn variable(element: EquationElement<S>) -> Self {
+        Expression::Variable(element.lowered())
+    }
+
+    // The element computed by every operation of the tree
+    pub fn lower(&self) -> Element<S> {
+        match self {
+            Expression::Constant(value) => Element::new_known(value.clone()),
+            Expression::Variable(element) => Element::from(element.clone()),
+            Expression::Unary(UnaryOperator::Neg, operand) => -operand.lower(),
+            // Constants are scalars, so they keep the dimension of the other side
+            Expression::Binary(operator, lhs, rhs) => match (operator, lhs.as_ref(), rhs.as_ref()) {
+                (BinaryOperator::Mul, Expression::Constant(lhs), rhs) => rhs.lower() * lhs.clone(),
+                (BinaryOperator::Mul, lhs, Expression::Constant(rhs)) => lhs.lower() * rhs.clone(),
+                (BinaryOperator::Div, lhs, Expression::Constant(rhs)) => lhs.lower() / rhs.clone(),
+                (BinaryOperator::Div, Expression::Constant(lhs), rhs) => Element::known_over(lhs.clone(), rhs.lower()),
+                (BinaryOperator::Add, lhs, rhs) => lhs.lower() + rhs.lower(),
+                (BinaryOperator::Sub, lhs, rhs) => lhs.lower() - rhs.lower(),
+                (BinaryOperator::Mul, lhs, rhs) => lhs.lower() * rhs.lower(),
+                (BinaryOperator::Div, lhs, rhs) => lhs.lower() / rhs.lower(),
+            },
+            Expression::Function(function, argument) => argument.lower().call(function.clone()),
+        }
+    }
+
+    pub fn sqrt(self) -> Self {
+        Expression::Function(Function::Sqrt, Box::new(self))
+    }
+
+    pub fn exp(self) -> Self {
+        Expression::Function(Function::Exp, Box::new(self))
+    }
+
+    pub fn ln(self) -> Self {
+        Expression::Function(Function::Ln, Box::new(self))
+    }
+
+    pub fn sin(self) -> Self {
+        Expression::Function(Function::Sin, Box::new(self))
+    }
+
+    pub fn cos(self) -> Self {
+        Expression::Function(Function::Cos, Box::new(self))
+    }
+
+    pub fn powf(self, exponent: S) -> Self {
+        Expression::Function(Function::Powf(exponent), Box::new(self))
+    }
+
+    // The relation keeps both trees along with their elements
+    pub fn equals<R>(self, rhs: R) -> Relation<S>
+    where
+        R: Into<Expression<S>>,
+    {
+        Relation::from_expressions(self, rhs.into())
+    }
+
+    pub fn to_latex(&self) -> String {
+        self.formula(&Names::of_expressions(&[self])).write(Notation::Latex)
+    }
+
+    pub fn to_mathml(&self) -> String {
+        self.formula(&Names::of_expressions(&[self])).write(Notation::MathMl)
+    }
+
+    // The formula follows the tree, so '2 * (x + 1)' is not expanded as the element is
+    pub(crate) fn formula(&self, names: &Names) -> Formula {
+        match self {
+            Expression::Constant(value) => Formula::number(value),
+            Expression::Variable(element) => element.formula(names),
+            Expression::Unary(UnaryOperator::Neg, operand) => Formula::Negative(Box::new(operand.formula(names))),
+            Expression::Binary(BinaryOperator::Add, lhs, rhs) => {
+                Formula::sum(vec![lhs.formula(names), Formula::Sum(vec![(false, rhs.formula(names))])])
+            },
+            Expression::Binary(BinaryOperator::Sub, lhs, rhs) => {
+                Formula::sum(vec![lhs.formula(names), Formula::Sum(vec![(true, rhs.formula(names))])])
+            },
+            Expression::Binary(BinaryOperator::Mul, lhs, rhs) => {
+                Formula::product(vec![lhs.formula(names), rhs.formula(names)])
+            },
+            Expression::Binary(BinaryOperator::Div, lhs, rhs) => {
+                Formula::Fraction(Box::new(lhs.formula(names)), Box::new(rhs.formula(names)))
+            },
+            Expression::Function(function, argument) => Formula::call(function, argument.formula(names)),
+        }
+    }
+
+    // The unknowns written without a name
+    pub(crate) fn unnamed_unknowns(&self, unknowns: &mut BTreeSet<UnknownId>) {
+        match self {
+            Expression::Constant(_) => (),
+            Expression::Variable(element) => {
+                if element.name().is_none() {
+                    unknowns.extend(element.unknown_id());
+                }
+            },
+            Expression::Unary(_, operand) | Expression::Function(_, operand) => operand.unnamed_unknowns(unknowns),
+            Expression::Binary(_, lhs, rhs) => {
+                lhs.unnamed_unknowns(unknowns);
+                rhs.unnamed_unknowns(unknowns);
+            },
+        }
+    }
+
+    fn binary(self, operator: BinaryOperator, rhs: Self) -> Self {
+        Expression::Binary(operator, Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<S: Scalar> From<S> for Expression<S> {
+    fn from(value: S) -> Self {
+        Expression::Constant(value)
+    }
+}
+
+impl<S: Scalar> From<EquationElement<S>> for Expression<S> {
+    fn from(element: EquationElement<S>) -> Self {
+        Expression::variable(element)
+    }
+}
+
+impl<S: Scalar> From<Expression<S>> for Element<S> {
+    fn from(expression: Expression<S>) -> Self {
+        expression.lower()
+    }
+}
+
+impl<S: Scalar> Display for Expression<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.formula(&Names::of_expressions(&[self])).write(Notation::Text))
+    }
+}
+
+impl<S: Scalar> PartialEq for Expression<S> {
+    fn eq(&self, rhs: &Self) -> bool {
+        match (self, rhs) {
+            (Expression::Constant(value1), Expression::Constant(value2)) => value1 == value2,
+            (Expression::Variable(element1), Expression::Variable(element2)) => element1 == element2,
+            (Expression::Unary(operator1, operand1), Expression::Unary(operator2, operand2)) => {
+                operator1 == operator2 && operand1 == operand2
+            },
+            (Expression::Binary(operator1, lhs1, rhs1), Expression::Binary(operator2, lhs2, rhs2)) => {
+                operator1 == operator2 && lhs1 == lhs2 && rhs1 == rhs2
+            },
+            (Expression::Function(function1, argument1), Expression::Function(function2, argument2)) => {
+                function1 == function2 && argument1 == argument2
+            },
+            _ => false,
+        }
+    }
+}
+
+impl<S: Scalar> Neg for Expression<S> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Expression::Unary(UnaryOperator::Neg, Box::new(self))
+    }
+}
+
+macro_rules! impl_expression_operator {
+    ($trait:ident, $method:ident, $operator:expr) => {
+        impl<S: Scalar> $trait for Expression<S> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                self.binary($operator, rhs)
+            }
+        }
+
+        impl<S: Scalar> $trait<S> for Expression<S> {
+            type Output = Self;
+
+            fn $method(self, rhs: S) -> Self {
+                self.binary($operator, Expression::Constant(rhs))
+            }
+        }
+
+        impl<S: Scalar> $trait<EquationElement<S>> for Expression<S> {
+            type Output = Self;
+
+            fn $method(self, rhs: EquationElement<S>) -> Self {
+                self.binary($operator, Expression::variable(rhs))
+            }
+        }
+    };
+}
+
+impl_expression_operator!(Add, add, BinaryOperator::Add);
+impl_expression_operator!(Sub, sub, BinaryOperator::Sub);
+impl_expression_operator!(Mul, mul, BinaryOperator::Mul);
+impl_expression_operator!(Div, div, BinaryOperator::Div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::Error;
+    use crate::relation::Method;
+    use crate::roots::Roots;
+    use crate::unit::Dimension;
+
+    #[test]
+    fn test_build_expression() {
+        let x = Rc::new(RefCell::new(0.));
+        let expression = 2. * Expression::unknown("x", x.clone()) + 1.;
+        let Expression::Binary(BinaryOperator::Add, lhs, rhs) = &expression else {
+            panic!("{:?} is not a sum", expression);
+        };
+        assert_eq!(rhs.as_ref(), &Expression::Constant(1.));
+        let Expression::Binary(BinaryOperator::Mul, two, unknown) = lhs.as_ref() else {
+            panic!("{:?} is not a product", lhs);
+        };
+        assert_eq!(two.as_ref(), &Expression::constant(2.));
+        let Expression::Variable(unknown) = unknown.as_ref() else {
+            panic!("{:?} is not a variable", unknown);
+        };
+        assert_eq!(unknown.name(), Some("x".to_string()));
+        assert!(unknown.is_unknown());
+
+        let other = 2. * Expression::unknown("x", x.clone()) + 1.;
+        assert_eq!(expression, other);
+        let other = Expression::unknown("x", Rc::new(RefCell::new(0.))) * 2. + 1.;
+        assert_ne!(expression, other);
+    }
+
+    #[test]
+    fn test_lower_expression() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = || Expression::unknown("x", rc.clone());
+        let element = || Element::new_unknown(rc.clone());
+
+        let expression = (x() + 1.) * (x() - 1.) / 2.;
+        assert_eq!(expression.lower(), (element() + 1.) * (element() - 1.) / 2.);
+        assert_eq!(Element::from(-x() / 4.), element() * -0.25);
+
+        // Constants do not change the dimension of the other side
+        let length = EquationElement::<f64>::unknown().with_unit("m").unwrap();
+        let expression = 2. * Expression::<f64>::from(length.clone()) / 4.;
+        assert_eq!(expression.lower().dimension(), Some(Dimension::LENGTH));
+        let expression = 1. / Expression::<f64>::from(length);
+        assert_eq!(expression.lower().dimension(), Some(Dimension::NONE / Dimension::LENGTH));
+
+        let relation = (x() * x()).equals(4.);
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(vec![-2., 2.]));
+        let relation = x().exp().equals(1.);
+        let solution = relation.solve().unwrap();
+        assert_eq!(solution.method(), Method::Analytic);
+        assert_eq!(solution.roots(), &Roots::from(0.));
+
+        let relation = (Expression::constant(1.) / (x() - x())).equals(1.);
+        assert_eq!(relation.solve(), Err(Error::DivisionByZero));
+    }
+
+    #[test]
+    fn test_relation_keeps_expressions() {
+        let distance = EquationElement::known(100.).with_name("distance");
+        let time = EquationElement::unknown().with_name("time");
+        let lhs = Expression::from(distance) / time.clone();
+        let relation = lhs.clone().equals(20.);
+
+        assert_eq!(relation.expressions(), Some((&lhs, &Expression::Constant(20.))));
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(5.));
+        assert_eq!(Relation::new(lhs, 20.).expressions(), None);
+        assert_eq!(time.get_unknown_value(), Ok(0.));
+    }
+}
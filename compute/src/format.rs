@@ -0,0 +1,408 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::element::Element;
+use crate::expression::Expression;
+use crate::function::Function;
+use crate::polynomial::Polynomial;
+use crate::scalar::Scalar;
+use crate::unknown::UnknownId;
+
+// The notations formulas can be written in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Notation {
+    Text,
+    Latex,
+    MathMl,
+}
+
+// A formula laid out for writing, built from an element, an expression or a relation
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Formula {
+    Number(String),
+    // A name, with an index for the unknowns without one, like 'x_2'
+    Variable(String, Option<usize>),
+    // Terms flagged when they are subtracted
+    Sum(Vec<(bool, Formula)>),
+    Product(Vec<Formula>),
+    Fraction(Box<Formula>, Box<Formula>),
+    Power(Box<Formula>, Box<Formula>),
+    Negative(Box<Formula>),
+    Sqrt(Box<Formula>),
+    Call(&'static str, Box<Formula>),
+    // An equation or an inequality, with its symbol like '≤'
+    Relation(Box<Formula>, &'static str, Box<Formula>),
+}
+
+// The names of the unknowns, the ones without a name are written 'x', or 'x_1', 'x_2', ... if there are several
+pub(crate) struct Names {
+    named: BTreeMap<UnknownId, String>,
+    unnamed: Vec<UnknownId>,
+}
+
+impl Names {
+    pub(crate) fn new(named: BTreeMap<UnknownId, String>, unknowns: BTreeSet<UnknownId>) -> Self {
+        let unnamed = unknowns.into_iter().filter(|id| !named.contains_key(id)).collect();
+        Self { named, unnamed }
+    }
+
+    pub(crate) fn of<S: Scalar>(elements: &[&Element<S>]) -> Self {
+        let named = elements.iter().flat_map(|element| element.names().clone()).collect();
+        let unknowns = elements.iter().flat_map(|element| element.unknown_ids()).collect();
+        Self::new(named, unknowns)
+    }
+
+    pub(crate) fn of_expressions<S: Scalar>(expressions: &[&Expression<S>]) -> Self {
+        let mut unknowns = BTreeSet::new();
+        expressions.iter().for_each(|expression| expression.unnamed_unknowns(&mut unknowns));
+        Self::new(BTreeMap::new(), unknowns)
+    }
+
+    pub(crate) fn variable(&self, id: UnknownId) -> Formula {
+        if let Some(name) = self.named.get(&id) {
+            return Formula::Variable(name.clone(), None);
+        }
+        match self.unnamed.iter().position(|other| *other == id) {
+            Some(index) if self.unnamed.len() > 1 => Formula::Variable("x".to_string(), Some(index + 1)),
+            _ => Formula::Variable("x".to_string(), None),
+        }
+    }
+}
+
+impl Formula {
+    pub(crate) fn number<S: Scalar>(value: &S) -> Self {
+        if value.is_negative() {
+            Formula::Negative(Box::new(Formula::Number((-value.clone()).to_string())))
+        } else {
+            Formula::Number(value.to_string())
+        }
+    }
+
+    // Terms are written with the highest degree first, like '3·x^2 - x + 7'
+    pub(crate) fn polynomial<S, F>(polynomial: &Polynomial<S>, variable: F) -> Self
+    where
+        S: Scalar,
+        F: Fn(UnknownId) -> Formula,
+    {
+        let terms = polynomial.terms().into_iter().map(|(monomial, a)| {
+            let factors: Vec<Formula> = monomial
+                .iter()
+                .map(|(id, power)| match power {
+                    1 => variable(*id),
+                    _ => Formula::Power(Box::new(variable(*id)), Box::new(Formula::Number(power.to_string()))),
+                })
+                .collect();
+            Formula::term(a, factors)
+        });
+        Formula::sum(terms.collect())
+    }
+
+    pub(crate) fn fraction(numerator: Formula, denominator: Formula) -> Self {
+        if denominator == Formula::Number("1".to_string()) {
+            numerator
+        } else {
+            Formula::Fraction(Box::new(numerator), Box::new(denominator))
+        }
+    }
+
+    pub(crate) fn call<S: Scalar>(function: &Function<S>, argument: Formula) -> Self {
+        let argument = Box::new(argument);
+        match function {
+            Function::Sqrt => Formula::Sqrt(argument),
+            Function::Exp => Formula::Call("exp", argument),
+            Function::Ln => Formula::Call("ln", argument),
+            Function::Sin => Formula::Call("sin", argument),
+            Function::Cos => Formula::Call("cos", argument),
+            Function::Powf(exponent) => Formula::Power(argument, Box::new(Formula::number(exponent))),
+        }
+    }
+
+    // Negative terms are subtracted, nested sums are flattened
+    pub(crate) fn sum(terms: Vec<Formula>) -> Self {
+        let mut flattened = vec![];
+        for term in terms {
+            match term {
+                Formula::Sum(terms) => flattened.extend(terms),
+                Formula::Negative(term) => flattened.push((true, *term)),
+                term => flattened.push((false, term)),
+            }
+        }
+        match flattened.len() {
+            0 => Formula::Number("0".to_string()),
+            1 => match flattened.remove(0) {
+                (true, term) => Formula::Negative(Box::new(term)),
+                (false, term) => term,
+            },
+            _ => Formula::Sum(flattened),
+        }
+    }
+
+    pub(crate) fn product(factors: Vec<Formula>) -> Self {
+        let mut flattened = vec![];
+        for factor in factors {
+            match factor {
+                Formula::Product(factors) => flattened.extend(factors),
+                factor => flattened.push(factor),
+            }
+        }
+        match flattened.len() {
+            1 => flattened.remove(0),
+            _ => Formula::Product(flattened),
+        }
+    }
+
+    // 'a·factors', where 'a' is left out if it is 1
+    fn term<S: Scalar>(a: &S, factors: Vec<Formula>) -> Self {
+        if a.is_negative() {
+            return Formula::Negative(Box::new(Formula::term(&-a.clone(), factors)));
+        }
+        if factors.is_empty() || *a != S::one() {
+            Formula::product([vec![Formula::Number(a.to_string())], factors].concat())
+        } else {
+            Formula::product(factors)
+        }
+    }
+
+    pub(crate) fn write(&self, notation: Notation) -> String {
+        match notation {
+            Notation::Text => self.text(),
+            Notation::Latex => self.latex(),
+            Notation::MathMl => format!("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>", self.mathml()),
+        }
+    }
+
+    // How tightly the formula binds, operands binding less tightly than their operation are parenthesized
+    fn precedence(&self, notation: Notation) -> u8 {
+        match self {
+            Formula::Relation(_, _, _) => 0,
+            Formula::Sum(_) | Formula::Negative(_) => 1,
+            // A number written as a sum, like the complex '3+4i'
+            Formula::Number(number) if is_sum(number) => 1,
+            Formula::Product(_) => 2,
+            Formula::Fraction(_, _) if notation == Notation::Text => 2,
+            Formula::Power(_, _) => 3,
+            Formula::Call("exp", _) if notation != Notation::Text => 3,
+            _ => 4,
+        }
+    }
+
+    fn text(&self) -> String {
+        let operand = |formula: &Formula, precedence: u8| {
+            if formula.precedence(Notation::Text) <= precedence {
+                format!("({})", formula.text())
+            } else {
+                formula.text()
+            }
+        };
+        match self {
+            Formula::Number(number) => number.clone(),
+            Formula::Variable(name, None) => name.clone(),
+            Formula::Variable(name, Some(index)) => format!("{}_{}", name, index),
+            Formula::Sum(terms) => Formula::write_sum(terms, |term, subtracted| match subtracted {
+                true => operand(term, 1),
+                false => term.text(),
+            }),
+            Formula::Product(factors) => factors.iter().map(|factor| operand(factor, 1)).collect::<Vec<_>>().join("·"),
+            Formula::Fraction(numerator, denominator) => {
+                format!("{} / {}", operand(numerator, 1), operand(denominator, 2))
+            },
+            Formula::Power(base, exponent) => format!("{}^{}", operand(base, 3), operand(exponent, 3)),
+            Formula::Negative(operand_formula) => format!("-{}", operand(operand_formula, 1)),
+            Formula::Sqrt(argument) => format!("sqrt({})", argument.text()),
+            Formula::Call(function, argument) => format!("{}({})", function, argument.text()),
+            Formula::Relation(lhs, symbol, rhs) => format!("{} {} {}", lhs.text(), symbol, rhs.text()),
+        }
+    }
+
+    fn latex(&self) -> String {
+        let operand = |formula: &Formula, precedence: u8| {
+            if formula.precedence(Notation::Latex) <= precedence {
+                format!("\\left({}\\right)", formula.latex())
+            } else {
+                formula.latex()
+            }
+        };
+        match self {
+            Formula::Number(number) => number.clone(),
+            Formula::Variable(name, index) => {
+                let name = match name.chars().count() {
+                    1 => name.clone(),
+                    _ => format!("\\mathrm{{{}}}", name.replace('_', "\\_")),
+                };
+                match index {
+                    Some(index) => format!("{}_{{{}}}", name, index),
+                    None => name,
+                }
+            },
+            Formula::Sum(terms) => Formula::write_sum(terms, |term, subtracted| match subtracted {
+                true => operand(term, 1),
+                false => term.latex(),
+            }),
+            Formula::Product(factors) => {
+                factors.iter().map(|factor| operand(factor, 1)).collect::<Vec<_>>().join(" \\cdot ")
+            },
+            Formula::Fraction(numerator, denominator) => {
+                format!("\\frac{{{}}}{{{}}}", numerator.latex(), denominator.latex())
+            },
+            Formula::Power(base, exponent) => format!("{}^{{{}}}", operand(base, 3), exponent.latex()),
+            Formula::Negative(operand_formula) => format!("-{}", operand(operand_formula, 1)),
+            Formula::Sqrt(argument) => format!("\\sqrt{{{}}}", argument.latex()),
+            Formula::Call("exp", argument) => format!("e^{{{}}}", argument.latex()),
+            Formula::Call(function, argument) => format!("\\{}\\left({}\\right)", function, argument.latex()),
+            Formula::Relation(lhs, symbol, rhs) => {
+                let symbol = match *symbol {
+                    "≤" => "\\le",
+                    "≥" => "\\ge",
+                    symbol => symbol,
+                };
+                format!("{} {} {}", lhs.latex(), symbol, rhs.latex())
+            },
+        }
+    }
+
+    fn mathml(&self) -> String {
+        let parenthesized = |formula: &Formula| format!("<mrow><mo>(</mo>{}<mo>)</mo></mrow>", formula.mathml());
+        let operand = |formula: &Formula, precedence: u8| {
+            if formula.precedence(Notation::MathMl) <= precedence {
+                parenthesized(formula)
+            } else {
+                formula.mathml()
+            }
+        };
+        match self {
+            Formula::Number(number) => format!("<mn>{}</mn>", escape(number)),
+            Formula::Variable(name, None) => format!("<mi>{}</mi>", escape(name)),
+            Formula::Variable(name, Some(index)) => format!("<msub><mi>{}</mi><mn>{}</mn></msub>", escape(name), index),
+            Formula::Sum(terms) => {
+                let mut written = String::new();
+                for (i, (subtracted, term)) in terms.iter().enumerate() {
+                    match (i, subtracted) {
+                        (_, true) => written += &format!("<mo>-</mo>{}", operand(term, 1)),
+                        (0, false) => written += &term.mathml(),
+                        (_, false) => written += &format!("<mo>+</mo>{}", term.mathml()),
+                    }
+                }
+                format!("<mrow>{}</mrow>", written)
+            },
+            Formula::Product(factors) => {
+                let factors: Vec<String> = factors.iter().map(|factor| operand(factor, 1)).collect();
+                format!("<mrow>{}</mrow>", factors.join("<mo>&#x22C5;</mo>"))
+            },
+            Formula::Fraction(numerator, denominator) => {
+                format!("<mfrac><mrow>{}</mrow><mrow>{}</mrow></mfrac>", numerator.mathml(), denominator.mathml())
+            },
+            Formula::Power(base, exponent) => {
+                format!("<msup><mrow>{}</mrow><mrow>{}</mrow></msup>", operand(base, 3), exponent.mathml())
+            },
+            Formula::Negative(operand_formula) => format!("<mrow><mo>-</mo>{}</mrow>", operand(operand_formula, 1)),
+            Formula::Sqrt(argument) => format!("<msqrt>{}</msqrt>", argument.mathml()),
+            Formula::Call("exp", argument) => format!("<msup><mi>e</mi><mrow>{}</mrow></msup>", argument.mathml()),
+            Formula::Call(function, argument) => {
+                format!("<mrow><mi>{}</mi><mo>&#x2061;</mo>{}</mrow>", function, parenthesized(argument))
+            },
+            Formula::Relation(lhs, symbol, rhs) => {
+                format!("<mrow>{}<mo>{}</mo>{}</mrow>", lhs.mathml(), escape(symbol), rhs.mathml())
+            },
+        }
+    }
+
+    // 'a + b - c', with a leading '-' if the first term is subtracted
+    fn write_sum<F>(terms: &[(bool, Formula)], write: F) -> String
+    where
+        F: Fn(&Formula, bool) -> String,
+    {
+        let mut written = String::new();
+        for (i, (subtracted, term)) in terms.iter().enumerate() {
+            let sign = match (i, subtracted) {
+                (0, false) => "",
+                (0, true) => "-",
+                (_, false) => " + ",
+                (_, true) => " - ",
+            };
+            written += sign;
+            written += &write(term, *subtracted);
+        }
+        written
+    }
+}
+
+// Whether a number has a sign past its first character, other than in an exponent like '1e-9'
+fn is_sum(number: &str) -> bool {
+    let chars: Vec<char> = number.chars().collect();
+    (1..chars.len()).any(|i| matches!(chars[i], '+' | '-') && chars[i - 1] != 'e')
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::equation::EquationElement;
+    use crate::rational::Rational;
+
+    use super::*;
+
+    #[test]
+    fn test_write_elements() {
+        let name = EquationElement::<f64>::unknown().with_name("x");
+        let x = || name.clone();
+        let rc = Rc::new(RefCell::new(0.));
+        let y = || Element::new_unknown(rc.clone());
+
+        assert_eq!((3. * x() + 7.).to_string(), "3·x + 7");
+        assert_eq!((x() * x() - x() * 2. - 1.).to_string(), "x^2 - 2·x - 1");
+        assert_eq!((-y() + 0.5).to_string(), "-x + 0.5");
+        assert_eq!(((y() + 1.) / (y() * 2.)).to_string(), "(x + 1) / (2·x)");
+        assert_eq!((y() * 3.).exp().to_string(), "exp(3·x)");
+        assert_eq!((y().sqrt() + y().powf(0.5)).to_string().matches("sqrt(x)").count(), 1);
+        assert_eq!(Element::new_known(0.).to_string(), "0");
+        assert_eq!(Element::new_known(Rational::new(-2, 3)).to_string(), "-2/3");
+
+        // Unknowns without a name are numbered
+        let z = Element::new_unknown(Rc::new(RefCell::new(0.)));
+        let written = (y() + z).to_string();
+        assert!(written == "x_1 + x_2" || written == "x_2 + x_1", "{}", written);
+    }
+
+    #[test]
+    fn test_write_latex_and_mathml() {
+        let distance = EquationElement::<f64>::unknown().with_name("distance");
+        let time = EquationElement::<f64>::unknown().with_name("t");
+        let element = (distance.clone() - 1.) / (time.clone() * time.clone());
+
+        assert_eq!(element.to_latex(), "\\frac{\\mathrm{distance} - 1}{t^{2}}");
+        assert_eq!(
+            element.to_mathml(),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mfrac><mrow><mrow><mi>distance</mi><mo>-</mo><mn>1</mn>\
+             </mrow></mrow><mrow><msup><mrow><mi>t</mi></mrow><mrow><mn>2</mn></mrow></msup></mrow></mfrac></math>"
+        );
+        assert_eq!((2. * time.clone()).exp().to_latex(), "e^{2 \\cdot t}");
+        assert_eq!((time.clone() + 1.).ln().to_latex(), "\\ln\\left(t + 1\\right)");
+        let mathml = "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><msqrt><mi>t</mi></msqrt></math>";
+        assert_eq!(time.clone().sqrt().to_mathml(), mathml);
+        assert_eq!(EquationElement::<f64>::unknown().with_name("max_speed").to_latex(), "\\mathrm{max\\_speed}");
+    }
+
+    #[test]
+    fn test_write_relations() {
+        let speed = EquationElement::<f64>::known(20.);
+        let distance = EquationElement::known(100.).with_name("distance");
+        let time = EquationElement::unknown().with_name("time");
+
+        // Elements fold the knowns, expressions keep the tree
+        let relation = speed.clone().equals(distance.clone() / time.clone());
+        assert_eq!(relation.to_string(), "20 = 100 / time");
+        let relation = Expression::from(speed).equals(Expression::from(distance) / time);
+        assert_eq!(relation.to_string(), "20 = distance / time");
+        assert_eq!(relation.to_latex(), "20 = \\frac{\\mathrm{distance}}{\\mathrm{time}}");
+
+        let x = || Expression::<f64>::unknown("x", Rc::new(RefCell::new(0.)));
+        let expression = 2. * (x() + 1.) - -(x() - 3.);
+        assert_eq!(expression.to_string(), "2·(x + 1) - (-(x - 3))");
+        assert_eq!(EquationElement::<f64>::unknown().to_string(), "x");
+        assert_eq!(EquationElement::<f64>::known(-4.).to_string(), "-4");
+    }
+}
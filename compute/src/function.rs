@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use crate::element::Element;
+use crate::scalar::Scalar;
+use crate::unknown::UnknownId;
+
+// The transcendental functions an element can be passed to
+#[derive(Clone, Debug, PartialEq)]
+pub enum Function<S = f64> {
+    Sqrt,
+    Exp,
+    Ln,
+    Sin,
+    Cos,
+    // 'x^p' for a non integer 'p', only defined for 'x >= 0'
+    Powf(S),
+}
+
+impl<S: Scalar> Function<S> {
+    // Value of the function computed as f64, None out of its domain
+    pub(crate) fn evaluate_f64(&self, x: f64) -> Option<f64> {
+        let value = match self {
+            Function::Sqrt => x.sqrt(),
+            Function::Exp => x.exp(),
+            Function::Ln => x.ln(),
+            Function::Sin => x.sin(),
+            Function::Cos => x.cos(),
+            Function::Powf(exponent) => x.powf(exponent.to_f64()?),
+        };
+        Some(value).filter(|value| !value.is_nan())
+    }
+
+    // The function giving back the argument, if there is a single one for every value
+    pub(crate) fn inverse(&self) -> Option<Function<S>> {
+        match self {
+            Function::Sqrt => Some(Function::Powf(S::one() + S::one())),
+            Function::Exp => Some(Function::Ln),
+            Function::Ln => Some(Function::Exp),
+            Function::Powf(exponent) => Some(Function::Powf(S::one() / exponent.clone())),
+            Function::Sin | Function::Cos => None,
+        }
+    }
+
+    // The same function over another scalar
+    pub(crate) fn map<T, F>(&self, convert: F) -> Function<T>
+    where
+        F: Fn(&S) -> T,
+    {
+        match self {
+            Function::Sqrt => Function::Sqrt,
+            Function::Exp => Function::Exp,
+            Function::Ln => Function::Ln,
+            Function::Sin => Function::Sin,
+            Function::Cos => Function::Cos,
+            Function::Powf(exponent) => Function::Powf(convert(exponent)),
+        }
+    }
+
+    // Whether the function reaches 'value', only for the invertible functions
+    pub(crate) fn reaches(&self, value: &S) -> bool {
+        let value = value.to_f64().unwrap_or(f64::NAN);
+        match self {
+            Function::Sqrt | Function::Powf(_) => value >= 0.,
+            Function::Exp => value > 0.,
+            Function::Ln => !value.is_nan(),
+            Function::Sin | Function::Cos => (-1. ..=1.).contains(&value),
+        }
+    }
+}
+
+// A function applied to an element, seen as one more unknown by the polynomials
+#[derive(Clone, Debug)]
+pub(crate) struct Atom<S> {
+    pub(crate) function: Function<S>,
+    pub(crate) argument: Element<S>,
+}
+
+impl<S: Scalar> Atom<S> {
+    pub(crate) fn evaluate(&self, values: &BTreeMap<UnknownId, S>) -> Option<S> {
+        self.evaluate_as(values, &S::clone)
+    }
+
+    pub(crate) fn evaluate_as<T, F>(&self, values: &BTreeMap<UnknownId, T>, convert: &F) -> Option<T>
+    where
+        T: Scalar,
+        F: Fn(&S) -> T,
+    {
+        self.argument.evaluate_as(values, convert)?.apply(&self.function.map(convert))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::error::Error;
+    use crate::relation::Method;
+    use crate::roots::Roots;
+
+    fn check_root(element: Element, rhs: f64, root: f64, method: Method) {
+        let solution = element.equals(rhs).solve().unwrap();
+        let found = *solution.roots().single().unwrap();
+        assert!((found - root).abs() < 1e-9, "{} != {}", found, root);
+        assert_eq!(solution.method(), method);
+        assert!(solution.residual() < 1e-9);
+    }
+
+    #[test]
+    fn test_analytic_inversion() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = || Element::new_unknown(rc.clone());
+
+        check_root(x().exp(), 5., 5f64.ln(), Method::Analytic);
+        check_root(2. * (x() + 1.).ln() - 1., 3., 1f64.exp().powi(2) - 1., Method::Analytic);
+        check_root(x().sqrt(), 3., 9., Method::Analytic);
+        check_root((x() * 2.).powf(0.5), 4., 8., Method::Analytic);
+        check_root(x().sqrt().exp(), 5., 5f64.ln().powi(2), Method::Analytic);
+        check_root(1. / x().exp(), 2., -2f64.ln(), Method::Analytic);
+
+        // Out of the image of the function
+        let solution = x().exp().equals(-1.).solve().unwrap();
+        assert_eq!(solution.roots(), &Roots::default());
+        let solution = x().sqrt().equals(-1.).solve().unwrap();
+        assert_eq!(solution.roots(), &Roots::default());
+
+        // sqrt(x^2) = 2
+        let solution = (x() * x()).sqrt().equals(2.).solve().unwrap();
+        assert_eq!(solution.roots(), &Roots::from(vec![-2., 2.]));
+    }
+
+    #[test]
+    fn test_numeric_roots() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = || Element::new_unknown(rc.clone());
+
+        check_root(x().sin(), 0.5, std::f64::consts::FRAC_PI_6, Method::Brent);
+        check_root(x().exp() + x(), 2., 0.4428544010023885, Method::Brent);
+        check_root(x().cos() - x(), 0., 0.7390851332151607, Method::Brent);
+
+        // The root closest to the current value of the unknown
+        *rc.borrow_mut() = 3.;
+        check_root(x().sin(), 0., std::f64::consts::PI, Method::Brent);
+        *rc.borrow_mut() = 0.;
+
+        let solution = x().exp().equals(x() - 1.).solve();
+        assert_eq!(solution, Err(Error::NoConvergence));
+    }
+
+    #[test]
+    fn test_functions_of_constants() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = Element::new_unknown(rc);
+
+        assert_eq!(Element::new_known(4.).sqrt(), 2.);
+        assert_eq!(Element::new_known(0.).exp(), 1.);
+        assert_eq!(Element::new_known(2.).powf(3.), 8.);
+        assert_eq!(x.clone().powf(2.), x.clone() * x.clone());
+        assert_eq!(x.clone().powf(-1.), 1. / x.clone());
+        assert_eq!((x.clone() + Element::new_known(-1.).ln()).equals(0.).solve(), Err(Error::OutOfDomain));
+        assert_eq!(x.equals(Element::new_known(-4.).sqrt()).solve(), Err(Error::OutOfDomain));
+    }
+}
@@ -0,0 +1,386 @@
+use std::fmt::{self, Display};
+use std::ops::Bound;
+
+use crate::element::Element;
+use crate::error::Error;
+use crate::format::{Formula, Names, Notation};
+use crate::scalar::Scalar;
+use crate::unknown::UnknownId;
+
+fn log_compute(message: String) {
+    logger::log(logger::LogStep::Compute, &message);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    // The comparison once both sides are multiplied by a negative number
+    pub fn flipped(self) -> Self {
+        match self {
+            Comparison::Less => Comparison::Greater,
+            Comparison::LessOrEqual => Comparison::GreaterOrEqual,
+            Comparison::Greater => Comparison::Less,
+            Comparison::GreaterOrEqual => Comparison::LessOrEqual,
+        }
+    }
+
+    pub fn holds<S: PartialOrd>(self, lhs: &S, rhs: &S) -> bool {
+        match self {
+            Comparison::Less => lhs < rhs,
+            Comparison::LessOrEqual => lhs <= rhs,
+            Comparison::Greater => lhs > rhs,
+            Comparison::GreaterOrEqual => lhs >= rhs,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "≤",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => "≥",
+        }
+    }
+}
+
+// The values between two bounds, each one being included, excluded or unbounded
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bounds<S = f64> {
+    lower: Bound<S>,
+    upper: Bound<S>,
+}
+
+impl<S: Scalar + PartialOrd> Bounds<S> {
+    pub fn new(lower: Bound<S>, upper: Bound<S>) -> Self {
+        Self { lower, upper }
+    }
+
+    // Every value
+    pub fn all() -> Self {
+        Self::new(Bound::Unbounded, Bound::Unbounded)
+    }
+
+    // The values 'x' such that 'x comparison value'
+    pub fn compared_to(comparison: Comparison, value: S) -> Self {
+        match comparison {
+            Comparison::Less => Self::new(Bound::Unbounded, Bound::Excluded(value)),
+            Comparison::LessOrEqual => Self::new(Bound::Unbounded, Bound::Included(value)),
+            Comparison::Greater => Self::new(Bound::Excluded(value), Bound::Unbounded),
+            Comparison::GreaterOrEqual => Self::new(Bound::Included(value), Bound::Unbounded),
+        }
+    }
+
+    pub fn lower(&self) -> &Bound<S> {
+        &self.lower
+    }
+
+    pub fn upper(&self) -> &Bound<S> {
+        &self.upper
+    }
+
+    pub fn contains(&self, value: &S) -> bool {
+        let above = match &self.lower {
+            Bound::Included(lower) => value >= lower,
+            Bound::Excluded(lower) => value > lower,
+            Bound::Unbounded => true,
+        };
+        let below = match &self.upper {
+            Bound::Included(upper) => value <= upper,
+            Bound::Excluded(upper) => value < upper,
+            Bound::Unbounded => true,
+        };
+        above && below
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match (&self.lower, &self.upper) {
+            (Bound::Included(lower), Bound::Included(upper)) => lower > upper,
+            (Bound::Included(lower) | Bound::Excluded(lower), Bound::Included(upper) | Bound::Excluded(upper)) => {
+                lower >= upper
+            },
+            _ => false,
+        }
+    }
+
+    // The values in both bounds, the tightest bound is kept on each side
+    pub fn intersection(&self, other: &Self) -> Self {
+        let lower = match (&self.lower, &other.lower) {
+            (Bound::Unbounded, bound) | (bound, Bound::Unbounded) => bound.clone(),
+            (bound1, bound2) if bound_value(bound1) > bound_value(bound2) => bound1.clone(),
+            (bound1, bound2) if bound_value(bound1) < bound_value(bound2) => bound2.clone(),
+            (Bound::Excluded(_), _) => self.lower.clone(),
+            _ => other.lower.clone(),
+        };
+        let upper = match (&self.upper, &other.upper) {
+            (Bound::Unbounded, bound) | (bound, Bound::Unbounded) => bound.clone(),
+            (bound1, bound2) if bound_value(bound1) < bound_value(bound2) => bound1.clone(),
+            (bound1, bound2) if bound_value(bound1) > bound_value(bound2) => bound2.clone(),
+            (Bound::Excluded(_), _) => self.upper.clone(),
+            _ => other.upper.clone(),
+        };
+        Self::new(lower, upper)
+    }
+}
+
+fn bound_value<S>(bound: &Bound<S>) -> Option<&S> {
+    match bound {
+        Bound::Included(value) | Bound::Excluded(value) => Some(value),
+        Bound::Unbounded => None,
+    }
+}
+
+// Written like '[2, 5)', with infinite sides open
+impl<S: Display> Display for Bounds<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.lower {
+            Bound::Included(lower) => write!(f, "[{}", lower)?,
+            Bound::Excluded(lower) => write!(f, "({}", lower)?,
+            Bound::Unbounded => write!(f, "(-∞")?,
+        }
+        match &self.upper {
+            Bound::Included(upper) => write!(f, ", {}]", upper),
+            Bound::Excluded(upper) => write!(f, ", {})", upper),
+            Bound::Unbounded => write!(f, ", ∞)"),
+        }
+    }
+}
+
+// An inequality like 'lhs < rhs', solved for its single unknown
+#[derive(Clone, Debug)]
+pub struct Inequality<S = f64> {
+    lhs: Element<S>,
+    comparison: Comparison,
+    rhs: Element<S>,
+}
+
+impl<S: Scalar> Inequality<S> {
+    pub fn new<L, R>(lhs: L, comparison: Comparison, rhs: R) -> Self
+    where
+        L: Into<Element<S>>,
+        R: Into<Element<S>>,
+    {
+        Self {
+            lhs: lhs.into(),
+            comparison,
+            rhs: rhs.into(),
+        }
+    }
+
+    pub fn lhs(&self) -> &Element<S> {
+        &self.lhs
+    }
+
+    pub fn comparison(&self) -> Comparison {
+        self.comparison
+    }
+
+    pub fn rhs(&self) -> &Element<S> {
+        &self.rhs
+    }
+
+    // Both inequalities must hold
+    pub fn and(self, other: Inequality<S>) -> Conjunction<S> {
+        Conjunction {
+            inequalities: vec![self, other],
+        }
+    }
+
+    pub fn to_latex(&self) -> String {
+        self.formula().write(Notation::Latex)
+    }
+
+    pub fn to_mathml(&self) -> String {
+        self.formula().write(Notation::MathMl)
+    }
+
+    fn formula(&self) -> Formula {
+        let names = Names::of(&[&self.lhs, &self.rhs]);
+        Formula::Relation(
+            Box::new(self.lhs.formula(&names)),
+            self.comparison.symbol(),
+            Box::new(self.rhs.formula(&names)),
+        )
+    }
+}
+
+impl<S: Scalar + PartialOrd> Inequality<S> {
+    // The values of the unknown satisfying the inequality, which must be linear
+    pub fn solve(&self) -> Result<Bounds<S>, Error> {
+        self.solve_unknown().map(|(_, bounds)| bounds)
+    }
+
+    // Also gives the unknown, None if it cancelled out
+    fn solve_unknown(&self) -> Result<(Option<UnknownId>, Bounds<S>), Error> {
+        let element = self.lhs.clone() - self.rhs.clone();
+        element.error()?;
+        let scale = [&self.lhs, &self.rhs, &element]
+            .iter()
+            .fold(0., |max: f64, element| max.max(element.numerator().magnitude()));
+        let element = element.truncated(&S::default_tolerance(), scale);
+
+        let numerator = element.numerator();
+        let id = match Vec::from_iter(element.unknown_ids()).as_slice() {
+            // 'b comparison 0' holds for every value or for none
+            [] if element.unknowns().next().is_some() => {
+                let value = numerator.constant_term() / element.denominator().constant_term();
+                return if self.comparison.holds(&value, &S::zero()) {
+                    Ok((None, Bounds::all()))
+                } else {
+                    log_compute("Error: The inequality never holds".to_string());
+                    Err(Error::NoSolution)
+                };
+            },
+            [] => {
+                log_compute("Error: No unknown in the inequality".to_string());
+                return Err(Error::NoUnkown);
+            },
+            [id] => *id,
+            _ => {
+                log_compute("Error: Several unknowns in the inequality".to_string());
+                return Err(Error::SeveralUnknown);
+            },
+        };
+
+        // 'a * x + b comparison 0' with a constant denominator
+        let coefficients = numerator.univariate_coefficients(id).filter(|_| element.is_algebraic());
+        let (Some([b, a]), true) = (coefficients.as_deref(), element.denominator().is_constant()) else {
+            log_compute("Error: Only linear inequalities can be solved".to_string());
+            return Err(Error::NonLinear);
+        };
+        let denominator = element.denominator().constant_term();
+        let comparison = match (a < &S::zero()) != (denominator < S::zero()) {
+            true => self.comparison.flipped(),
+            false => self.comparison,
+        };
+        let value = -b.clone() / a.clone();
+        value.check()?;
+        // Not written '-0' when 'b' is null
+        let value = if value.is_zero() { S::zero() } else { value };
+        log_compute(format!("The unknown is {:?} {:?}", comparison, value));
+        Ok((Some(id), Bounds::compared_to(comparison, value)))
+    }
+}
+
+impl<S: Scalar> Display for Inequality<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.formula().write(Notation::Text))
+    }
+}
+
+// Inequalities on the same unknown that must all hold
+#[derive(Clone, Debug)]
+pub struct Conjunction<S = f64> {
+    inequalities: Vec<Inequality<S>>,
+}
+
+impl<S: Scalar> Conjunction<S> {
+    pub fn and(mut self, inequality: Inequality<S>) -> Self {
+        self.inequalities.push(inequality);
+        self
+    }
+
+    pub fn inequalities(&self) -> &[Inequality<S>] {
+        &self.inequalities
+    }
+}
+
+impl<S: Scalar + PartialOrd> Conjunction<S> {
+    // The intersection of the values satisfying each inequality, fails if it is empty
+    pub fn solve(&self) -> Result<Bounds<S>, Error> {
+        let mut unknown = None;
+        let mut bounds = Bounds::all();
+        for inequality in &self.inequalities {
+            let (id, solved) = inequality.solve_unknown()?;
+            if id.is_some() && unknown.is_some() && id != unknown {
+                log_compute("Error: The inequalities are on several unknowns".to_string());
+                return Err(Error::SeveralUnknown);
+            }
+            unknown = unknown.or(id);
+            bounds = bounds.intersection(&solved);
+        }
+        if bounds.is_empty() {
+            log_compute(format!("Error: No value is in {:?}", bounds));
+            return Err(Error::NoSolution);
+        }
+        Ok(bounds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::equation::EquationElement;
+    use crate::rational::Rational;
+
+    #[test]
+    fn test_solve_inequalities() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = || Element::new_unknown(rc.clone());
+
+        // 3x + 2 < 11
+        let bounds = (x() * 3. + 2.).lt(11.).solve().unwrap();
+        assert_eq!(bounds, Bounds::new(Bound::Unbounded, Bound::Excluded(3.)));
+        assert!(bounds.contains(&2.9) && !bounds.contains(&3.));
+        assert_eq!(bounds.to_string(), "(-∞, 3)");
+
+        // The direction flips for negative coefficients
+        let bounds = (x() * -2.).le(4.).solve().unwrap();
+        assert_eq!(bounds, Bounds::new(Bound::Included(-2.), Bound::Unbounded));
+        assert_eq!(bounds.to_string(), "[-2, ∞)");
+        let bounds = (Element::new_known(1.) / (x() / -4.)).gt(0.).solve();
+        assert_eq!(bounds, Err(Error::NonLinear));
+        let bounds = (x() / -4.).ge(1.).solve().unwrap();
+        assert_eq!(bounds, Bounds::new(Bound::Unbounded, Bound::Included(-4.)));
+
+        // Cancelled unknowns
+        assert_eq!((x() + 1.).lt(x() + 2.).solve(), Ok(Bounds::all()));
+        assert_eq!((x() + 1.).gt(x() + 2.).solve(), Err(Error::NoSolution));
+        assert_eq!((x() * x()).lt(1.).solve(), Err(Error::NonLinear));
+        assert_eq!(Element::new_known(1.).lt(2.).solve(), Err(Error::NoUnkown));
+    }
+
+    #[test]
+    fn test_solve_conjunctions() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = || Element::new_unknown(rc.clone());
+
+        let bounds = x().gt(2.).and(x().le(5.)).and((x() * 2.).lt(12.)).solve().unwrap();
+        assert_eq!(bounds, Bounds::new(Bound::Excluded(2.), Bound::Included(5.)));
+        assert_eq!(bounds.to_string(), "(2, 5]");
+
+        // The excluded bound is the tightest
+        let bounds = x().ge(2.).and(x().gt(2.)).and(x().le(3.)).and(x().lt(3.)).solve().unwrap();
+        assert_eq!(bounds, Bounds::new(Bound::Excluded(2.), Bound::Excluded(3.)));
+        let bounds = x().ge(2.).and(x().le(2.)).solve().unwrap();
+        assert!(bounds.contains(&2.));
+
+        assert_eq!(x().gt(5.).and(x().lt(2.)).solve(), Err(Error::NoSolution));
+        assert_eq!(x().ge(2.).and(x().lt(2.)).solve(), Err(Error::NoSolution));
+        let y = Element::new_unknown(Rc::new(RefCell::new(0.)));
+        assert_eq!(x().gt(0.).and(y.lt(1.)).solve(), Err(Error::SeveralUnknown));
+    }
+
+    #[test]
+    fn test_inequalities_of_fields() {
+        // budget >= cost * n, in exact numbers
+        let budget = EquationElement::known(Rational::from(100)).with_name("budget");
+        let cost = EquationElement::known(Rational::from(7)).with_name("cost");
+        let n = EquationElement::unknown().with_name("n");
+
+        let inequality = budget.ge(cost * n);
+        assert_eq!(inequality.to_string(), "100 ≥ 7·n");
+        assert_eq!(inequality.to_latex(), "100 \\ge 7 \\cdot n");
+        let bounds = inequality.solve().unwrap();
+        assert_eq!(bounds, Bounds::new(Bound::Unbounded, Bound::Included(Rational::new(100, 7))));
+    }
+}
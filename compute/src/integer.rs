@@ -0,0 +1,442 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::error::Error;
+use crate::relation::Relation;
+use crate::roots;
+use crate::scalar::{impl_scalar_lhs_ops, Scalar};
+use crate::unknown::{Unknown, UnknownId};
+
+fn log_compute(message: String) {
+    logger::log(logger::LogStep::Compute, &message);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Value {
+    Exact(i128),
+    // A division leaving a remainder
+    Inexact,
+    Overflow,
+    DivisionByZero,
+}
+
+// A whole number, for quantities like batch counts
+// Divisions must be exact: one leaving a remainder, like an overflow, poisons every later operation
+// Values are limited to i128, with no arbitrary precision: going past it is an 'Error::Overflow', never wrapped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Integer(Value);
+
+impl Integer {
+    pub const ZERO: Integer = Integer(Value::Exact(0));
+    pub const ONE: Integer = Integer(Value::Exact(1));
+
+    pub fn new(value: i128) -> Self {
+        Self(Value::Exact(value))
+    }
+
+    // None after an invalid operation
+    pub fn value(&self) -> Option<i128> {
+        match self.0 {
+            Value::Exact(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.value().is_some()
+    }
+
+    fn checked(value: Option<i128>) -> Self {
+        value.map_or(Self(Value::Overflow), Self::new)
+    }
+
+    // The exact values of both operands, or the first invalid one
+    fn operands(self, rhs: Self) -> Result<(i128, i128), Self> {
+        match (self.0, rhs.0) {
+            (Value::Exact(a), Value::Exact(b)) => Ok((a, b)),
+            (Value::Exact(_), _) => Err(rhs),
+            _ => Err(self),
+        }
+    }
+}
+
+impl From<i32> for Integer {
+    fn from(value: i32) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl From<i64> for Integer {
+    fn from(value: i64) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl From<i128> for Integer {
+    fn from(value: i128) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<u32> for Integer {
+    fn from(value: u32) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl From<u64> for Integer {
+    fn from(value: u64) -> Self {
+        Self::new(value.into())
+    }
+}
+
+// Counts, no wider than 64 bits on the supported targets
+impl From<usize> for Integer {
+    fn from(value: usize) -> Self {
+        Self::new(value as i128)
+    }
+}
+
+// Results of invalid operations can't be compared
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        self.operands(*rhs).ok().map(|(a, b)| a.cmp(&b))
+    }
+}
+
+impl Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Value::Exact(value) => write!(f, "{}", value),
+            Value::Inexact => write!(f, "inexact"),
+            Value::Overflow => write!(f, "overflow"),
+            Value::DivisionByZero => write!(f, "0/0"),
+        }
+    }
+}
+
+impl Add for Integer {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.operands(rhs).map_or_else(|invalid| invalid, |(a, b)| Self::checked(a.checked_add(b)))
+    }
+}
+
+impl Neg for Integer {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self.0 {
+            Value::Exact(value) => Self::checked(value.checked_neg()),
+            _ => self,
+        }
+    }
+}
+
+impl Sub for Integer {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.operands(rhs).map_or_else(|invalid| invalid, |(a, b)| Self::checked(a.checked_sub(b)))
+    }
+}
+
+impl Mul for Integer {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.operands(rhs).map_or_else(|invalid| invalid, |(a, b)| Self::checked(a.checked_mul(b)))
+    }
+}
+
+impl Div for Integer {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        match self.operands(rhs) {
+            Err(invalid) => invalid,
+            Ok((_, 0)) => Self(Value::DivisionByZero),
+            Ok((a, b)) if a.checked_rem(b) != Some(0) => Self(Value::Inexact),
+            Ok((a, b)) => Self::checked(a.checked_div(b)),
+        }
+    }
+}
+
+impl Scalar for Integer {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.value().map_or(f64::NAN, |value| value.unsigned_abs() as f64)
+    }
+
+    // No integer solves a relation whose solution needs a division with a remainder
+    fn check(&self) -> Result<(), Error> {
+        match self.0 {
+            Value::Exact(_) => Ok(()),
+            Value::Inexact => Err(Error::NoSolution),
+            Value::Overflow => Err(Error::Overflow),
+            Value::DivisionByZero => Err(Error::DivisionByZero),
+        }
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.value().map(|value| value as f64)
+    }
+
+    // Only whole floats are integers
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value)
+            .filter(|value| value.fract() == 0. && value.abs() < i128::MAX as f64)
+            .map(|value| Self::new(value as i128))
+    }
+
+    // The real roots are approximated as f64, then the closest integers must cancel the polynomial exactly
+    fn polynomial_roots(coefficients: &[Self]) -> Result<Vec<Self>, Error> {
+        let approximations = coefficients
+            .iter()
+            .map(Scalar::to_f64)
+            .collect::<Option<Vec<f64>>>()
+            .ok_or(Error::Overflow)?;
+        let mut roots: Vec<Self> = vec![];
+        for approximation in roots::real_roots(&approximations) {
+            let Some(root) = Self::from_f64(approximation.round()) else {
+                continue;
+            };
+            let value = coefficients
+                .iter()
+                .rev()
+                .fold(Self::zero(), |value, a| value * root + *a);
+            if value.is_zero() && !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        Ok(roots)
+    }
+}
+
+impl_scalar_lhs_ops!(Integer);
+
+// 'gcd(a, b)' with the Bézout coefficients 's' and 't' such that 'a * s + b * t = gcd(a, b)', and 'gcd(a, b) >= 0'
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut s0, mut s1) = (1, 0);
+    let (mut t0, mut t1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    if r0 < 0 {
+        (-r0, -s0, -t0)
+    } else {
+        (r0, s0, t0)
+    }
+}
+
+// Every integer solution of 'a * x + b * y = c', as 'x = x0 + k * dx, y = y0 + k * dy' for any integer 'k'
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diophantine {
+    unknowns: [Unknown<Integer>; 2],
+    particular: [i128; 2],
+    step: [i128; 2],
+}
+
+impl Diophantine {
+    // 'a' and 'b' must not be null
+    fn new(x: Unknown<Integer>, y: Unknown<Integer>, a: i128, b: i128, c: i128) -> Result<Self, Error> {
+        let (gcd, s, t) = extended_gcd(a, b);
+        // '0 * x + 0 * y = 0' holds for any integers
+        if gcd == 0 && c == 0 {
+            log_compute("Error: Every pair of integers is a solution of 0 = 0".to_string());
+            return Err(Error::InfiniteSolutions);
+        }
+        if gcd == 0 || c % gcd != 0 {
+            log_compute(format!("Error: gcd({}, {}) = {} does not divide {}", a, b, gcd, c));
+            return Err(Error::NoSolution);
+        }
+        let factor = c / gcd;
+        let (dx, dy) = (b / gcd, -a / gcd);
+        let (dx, dy) = if dx < 0 { (-dx, -dy) } else { (dx, dy) };
+        // The particular solution with the smallest non negative 'x'
+        let x0 = s.checked_mul(factor).ok_or(Error::Overflow)?;
+        let y0 = t.checked_mul(factor).ok_or(Error::Overflow)?;
+        let k = -x0.div_euclid(dx);
+        let x0 = k.checked_mul(dx).and_then(|shift| x0.checked_add(shift)).ok_or(Error::Overflow)?;
+        let y0 = k.checked_mul(dy).and_then(|shift| y0.checked_add(shift)).ok_or(Error::Overflow)?;
+        log_compute(format!("x = {} + k * {}, y = {} + k * {}", x0, dx, y0, dy));
+        Ok(Self {
+            unknowns: [x, y],
+            particular: [x0, y0],
+            step: [dx, dy],
+        })
+    }
+
+    // The unknowns in the order of the values of the solutions
+    pub fn unknowns(&self) -> &[Unknown<Integer>; 2] {
+        &self.unknowns
+    }
+
+    pub fn particular(&self) -> [i128; 2] {
+        self.particular
+    }
+
+    // The change of both unknowns from one solution to the next, with a positive step for the first one
+    pub fn step(&self) -> [i128; 2] {
+        self.step
+    }
+
+    // The solution for the parameter 'k', None if it overflows
+    pub fn at(&self, k: i128) -> Option<[i128; 2]> {
+        let x = self.step[0].checked_mul(k)?.checked_add(self.particular[0])?;
+        let y = self.step[1].checked_mul(k)?.checked_add(self.particular[1])?;
+        Some([x, y])
+    }
+
+    // Writes the solution for the parameter 'k' in the unknowns
+    pub fn set(&self, k: i128) -> Result<(), Error> {
+        let values = self.at(k).ok_or(Error::Overflow)?;
+        for (x, value) in self.unknowns.iter().zip(values) {
+            *x.unknown.borrow_mut() = Integer::new(value);
+        }
+        Ok(())
+    }
+}
+
+impl Relation<Integer> {
+    // Solves a linear relation in two unknowns, like '12 * x + 5 * y = 77', over the integers
+    pub fn solve_diophantine(&self) -> Result<Diophantine, Error> {
+        let element = self.lhs().clone() - self.rhs().clone();
+        element.error()?;
+        if !element.is_algebraic() || !element.denominator().is_constant() {
+            log_compute("Error: Non linear Diophantine equation".to_string());
+            return Err(Error::NonLinear);
+        }
+        let numerator = element.numerator();
+        let coefficients = numerator.linear_coefficients().ok_or(Error::NonLinear)?;
+        let coefficients: Vec<(UnknownId, i128)> = coefficients
+            .into_iter()
+            .map(|(id, a)| a.check().map(|_| (id, a.value().expect("the coefficient is checked"))))
+            .collect::<Result<_, _>>()?;
+        let constant = numerator.constant_term();
+        constant.check()?;
+
+        let unknown = |id: &UnknownId| {
+            element
+                .unknowns()
+                .find(|x| x.id() == *id)
+                .expect("every unknown of the polynomials is known by the element")
+                .clone()
+        };
+        match coefficients.as_slice() {
+            // Two unknowns are needed, 'solve' finds a single one
+            [] | [_] => Err(Error::NoUnkown),
+            [(x, a), (y, b)] => {
+                let c = constant.value().and_then(i128::checked_neg).ok_or(Error::Overflow)?;
+                Diophantine::new(unknown(x), unknown(y), *a, *b, c)
+            },
+            _ => Err(Error::SeveralUnknown),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::element::Element;
+    use crate::equation::EquationElement;
+    use crate::roots::Roots;
+
+    #[test]
+    fn test_integer_arithmetic() {
+        let seven = Integer::from(7);
+        assert_eq!(seven * Integer::from(6) / Integer::from(3), Integer::from(14));
+        assert_eq!((seven / Integer::from(2)).check(), Err(Error::NoSolution));
+        assert_eq!((seven / Integer::from(2) + seven).check(), Err(Error::NoSolution));
+        assert_eq!((seven / Integer::ZERO).check(), Err(Error::DivisionByZero));
+        assert_eq!((Integer::new(i128::MAX) + Integer::ONE).check(), Err(Error::Overflow));
+        assert_eq!(Integer::from_f64(3.), Some(Integer::from(3)));
+        assert_eq!(Integer::from_f64(3.5), None);
+        assert!(Integer::from(-2) < Integer::ONE);
+        assert_eq!(Integer::from(-2).to_string(), "-2");
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(extended_gcd(-4, 6), (2, 1, 1));
+    }
+
+    #[test]
+    fn test_integer_roots() {
+        let rc = Rc::new(RefCell::new(Integer::ZERO));
+        let x = || Element::new_unknown(rc.clone());
+
+        // 12x + 5 = 77
+        let relation = (x() * Integer::from(12) + Integer::from(5)).equals(Integer::from(77));
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(Integer::from(6)));
+        let relation = (x() * Integer::from(12) + Integer::from(5)).equals(Integer::from(78));
+        assert_eq!(relation.solve(), Err(Error::NoSolution));
+
+        // x / 2 = 3 and x / 4 + x / 4 = 3, whose denominators are kept rather than inverted
+        let relation = (x() / Integer::from(2)).equals(Integer::from(3));
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(Integer::from(6)));
+        let relation = (x() / Integer::from(4) + x() / Integer::from(4)).equals(Integer::from(3));
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(Integer::from(6)));
+        let relation = (x() / Integer::from(2)).equals(Integer::from(4) / Integer::from(3));
+        assert_eq!(relation.solve(), Err(Error::NoSolution));
+        assert_eq!((x() * Integer::from(6) / Integer::from(3)).to_string(), "2·x");
+
+        // x^2 = 16 and x^2 = 2
+        let relation = (x() * x()).equals(Integer::from(16));
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(vec![Integer::from(-4), Integer::from(4)]));
+        let relation = (x() * x()).equals(Integer::from(2));
+        assert!(relation.solve().unwrap().roots().is_empty());
+    }
+
+    #[test]
+    fn test_diophantine() {
+        let pallets = EquationElement::<Integer>::unknown().with_name("pallets");
+        let boxes = EquationElement::<Integer>::unknown().with_name("boxes");
+
+        // 12 * pallets + 5 * boxes = 77
+        let lhs = Integer::from(12) * pallets.clone() + Integer::from(5) * boxes.clone();
+        let relation = lhs.equals(Integer::from(77));
+        let solutions = relation.solve_diophantine().unwrap();
+        let (p, b) = match solutions.unknowns()[0].id() == pallets.unknown_id().unwrap() {
+            true => (0, 1),
+            false => (1, 0),
+        };
+        assert_eq!(solutions.step()[p].abs(), 5);
+        assert_eq!(solutions.step()[b].abs(), 12);
+        for k in -3..3 {
+            let values = solutions.at(k).unwrap();
+            assert_eq!(12 * values[p] + 5 * values[b], 77);
+        }
+        let k = (0..5).find(|k| solutions.at(*k).unwrap()[p] == 6).unwrap();
+        solutions.set(k).unwrap();
+        assert_eq!(pallets.get_unknown_value(), Ok(Integer::from(6)));
+        assert_eq!(boxes.get_unknown_value(), Ok(Integer::from(1)));
+
+        // 6 * pallets + 4 * boxes = 7 has no integer solution
+        let relation = (Integer::from(6) * pallets.clone() + Integer::from(4) * boxes.clone()).equals(Integer::from(7));
+        assert_eq!(relation.solve_diophantine(), Err(Error::NoSolution));
+        let relation = (pallets.clone() * pallets.clone() + boxes.clone()).equals(Integer::from(7));
+        assert_eq!(relation.solve_diophantine(), Err(Error::NonLinear));
+        assert_eq!(pallets.equals(Integer::from(7)).solve_diophantine(), Err(Error::NoUnkown));
+
+        let x = || Unknown::new_with_value(Rc::new(RefCell::new(Integer::ZERO)));
+        assert_eq!(Diophantine::new(x(), x(), 0, 0, 0), Err(Error::InfiniteSolutions));
+        assert_eq!(Diophantine::new(x(), x(), 0, 0, 7), Err(Error::NoSolution));
+        // The Bézout coefficient of 240 is -9, multiplied by nearly i128::MAX / 2
+        assert_eq!(Diophantine::new(x(), x(), 240, 46, i128::MAX - 1), Err(Error::Overflow));
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::error::Error;
+use crate::scalar::{impl_scalar_lhs_ops, Scalar};
+
+// Rounding of the basic operations towards -inf or +inf, exact results are kept as they are
+#[derive(Clone, Copy)]
+enum Rounding {
+    Down,
+    Up,
+}
+
+impl Rounding {
+    // Moves 'value' one float away in the rounding direction if the exact result lies that way
+    fn round(self, value: f64, error: f64) -> f64 {
+        match self {
+            _ if value.is_nan() => value,
+            Rounding::Down if value == f64::INFINITY && error.is_nan() => f64::MAX,
+            Rounding::Up if value == f64::NEG_INFINITY && error.is_nan() => f64::MIN,
+            Rounding::Down if error < 0. => value.next_down(),
+            Rounding::Up if error > 0. => value.next_up(),
+            _ => value,
+        }
+    }
+
+    fn add(self, a: f64, b: f64) -> f64 {
+        let sum = a + b;
+        if !sum.is_finite() {
+            let error = if a.is_finite() && b.is_finite() { f64::NAN } else { 0. };
+            return self.round(sum, error);
+        }
+        // Error free transformation of the sum
+        let b_part = sum - a;
+        let error = (a - (sum - b_part)) + (b - b_part);
+        self.round(sum, error)
+    }
+
+    fn mul(self, a: f64, b: f64) -> f64 {
+        // 0 * inf is 0 for bounds
+        if a == 0. || b == 0. {
+            return 0.;
+        }
+        let product = a * b;
+        if !product.is_finite() {
+            let error = if a.is_finite() && b.is_finite() { f64::NAN } else { 0. };
+            return self.round(product, error);
+        }
+        self.round(product, a.mul_add(b, -product))
+    }
+
+    fn div(self, a: f64, b: f64) -> f64 {
+        if a == 0. {
+            return 0.;
+        }
+        let quotient = a / b;
+        if !quotient.is_finite() || b.is_infinite() {
+            let error = if a.is_finite() && b.is_finite() { f64::NAN } else { 0. };
+            return self.round(quotient, error);
+        }
+        // The remainder 'a - quotient * b' has the sign of the error times the sign of 'b'
+        let remainder = (-quotient).mul_add(b, a);
+        self.round(quotient, remainder * b.signum())
+    }
+}
+
+// A closed set of reals '[lower, upper]' guaranteed to contain the exact value
+// Operations round their bounds outward, so that results always enclose every possible value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    lower: f64,
+    upper: f64,
+}
+
+impl Interval {
+    // The result of a division by '[0, 0]', contains nothing
+    const EMPTY: Interval = Interval { lower: f64::NAN, upper: f64::NAN };
+    const ENTIRE: Interval = Interval { lower: f64::NEG_INFINITY, upper: f64::INFINITY };
+
+    // Panics if the bounds are not ordered
+    pub fn new(lower: f64, upper: f64) -> Self {
+        assert!(lower <= upper, "the bounds of an interval must be ordered: [{}, {}]", lower, upper);
+        Self { lower, upper }
+    }
+
+    pub fn point(value: f64) -> Self {
+        Self::new(value, value)
+    }
+
+    // The interval '[value - tolerance, value + tolerance]'
+    pub fn with_tolerance(value: f64, tolerance: f64) -> Self {
+        let tolerance = tolerance.abs();
+        Self::new(Rounding::Down.add(value, -tolerance), Rounding::Up.add(value, tolerance))
+    }
+
+    pub fn lower(&self) -> f64 {
+        self.lower
+    }
+
+    pub fn upper(&self) -> f64 {
+        self.upper
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lower.is_nan()
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+
+    pub fn midpoint(&self) -> f64 {
+        self.lower / 2. + self.upper / 2.
+    }
+
+    pub fn width(&self) -> f64 {
+        Rounding::Up.add(self.upper, -self.lower)
+    }
+
+    // The smallest interval containing every 'a * b'
+    fn hull_of_products<F>(&self, rhs: &Self, operation: F) -> Self
+    where
+        F: Fn(Rounding, f64, f64) -> f64,
+    {
+        let pairs = [
+            (self.lower, rhs.lower),
+            (self.lower, rhs.upper),
+            (self.upper, rhs.lower),
+            (self.upper, rhs.upper),
+        ];
+        Self {
+            lower: pairs
+                .iter()
+                .map(|(a, b)| operation(Rounding::Down, *a, *b))
+                .fold(f64::INFINITY, f64::min),
+            upper: pairs
+                .iter()
+                .map(|(a, b)| operation(Rounding::Up, *a, *b))
+                .fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+impl From<f64> for Interval {
+    fn from(value: f64) -> Self {
+        Self::point(value)
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            write!(f, "∅")
+        } else {
+            write!(f, "[{}, {}]", self.lower, self.upper)
+        }
+    }
+}
+
+impl Add for Interval {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.is_empty() || rhs.is_empty() {
+            return Self::EMPTY;
+        }
+        Self {
+            lower: Rounding::Down.add(self.lower, rhs.lower),
+            upper: Rounding::Up.add(self.upper, rhs.upper),
+        }
+    }
+}
+
+impl Neg for Interval {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            lower: -self.upper,
+            upper: -self.lower,
+        }
+    }
+}
+
+impl Sub for Interval {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.is_empty() || rhs.is_empty() {
+            return Self::EMPTY;
+        }
+        self.hull_of_products(&rhs, Rounding::mul)
+    }
+}
+
+impl Div for Interval {
+    type Output = Self;
+
+    // A divisor containing 0 gives the hull of the values reached on each side of 0
+    fn div(self, rhs: Self) -> Self {
+        if self.is_empty() || rhs.is_empty() || rhs == Self::zero() {
+            return Self::EMPTY;
+        }
+        if !rhs.contains(0.) {
+            return self.hull_of_products(&rhs, Rounding::div);
+        }
+        if self == Self::zero() {
+            return self;
+        }
+        if self.contains(0.) || (rhs.lower < 0. && rhs.upper > 0.) {
+            return Self::ENTIRE;
+        }
+        match (self.lower > 0., rhs.lower == 0.) {
+            // [+, +] / [0, b]
+            (true, true) => Self::new(Rounding::Down.div(self.lower, rhs.upper), f64::INFINITY),
+            // [-, -] / [0, b]
+            (false, true) => Self::new(f64::NEG_INFINITY, Rounding::Up.div(self.upper, rhs.upper)),
+            // [+, +] / [a, 0]
+            (true, false) => Self::new(f64::NEG_INFINITY, Rounding::Up.div(self.lower, rhs.lower)),
+            // [-, -] / [a, 0]
+            (false, false) => Self::new(Rounding::Down.div(self.upper, rhs.lower), f64::INFINITY),
+        }
+    }
+}
+
+// Only linear relations can be solved with intervals, as roots of higher degree have no guaranteed enclosure here
+impl Scalar for Interval {
+    fn zero() -> Self {
+        Self::point(0.)
+    }
+
+    fn one() -> Self {
+        Self::point(1.)
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.lower.abs().max(self.upper.abs())
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        if self.is_empty() {
+            Err(Error::DivisionByZero)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(Self::point(value))
+    }
+}
+
+impl_scalar_lhs_ops!(Interval);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::element::Element;
+
+    #[test]
+    fn test_interval_arithmetic() {
+        let a = Interval::new(1., 2.);
+        let b = Interval::new(-3., 4.);
+        assert_eq!(a + b, Interval::new(-2., 6.));
+        assert_eq!(a - b, Interval::new(-3., 5.));
+        assert_eq!(a * b, Interval::new(-6., 8.));
+        assert_eq!(a / Interval::new(2., 4.), Interval::new(0.25, 1.));
+
+        // Exact results are not widened, inexact ones are rounded outward
+        assert_eq!(Interval::point(0.5) + Interval::point(0.25), Interval::point(0.75));
+        let third = Interval::one() / Interval::point(3.);
+        assert!(third.lower() < third.upper());
+        assert!(third.lower() * 3. <= 1. && third.upper() * 3. >= 1.);
+        let sum = Interval::point(0.1) + Interval::point(0.2);
+        assert!(sum.contains(0.1 + 0.2) && sum.lower() < sum.upper());
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let positive = Interval::new(1., 2.);
+        assert_eq!(positive / Interval::new(0., 4.), Interval::new(0.25, f64::INFINITY));
+        assert_eq!(positive / Interval::new(-4., 0.), Interval::new(f64::NEG_INFINITY, -0.25));
+        assert_eq!(-positive / Interval::new(0., 4.), Interval::new(f64::NEG_INFINITY, -0.25));
+        assert_eq!(positive / Interval::new(-1., 1.), Interval::ENTIRE);
+        assert_eq!(Interval::new(-1., 1.) / Interval::new(0., 1.), Interval::ENTIRE);
+        assert_eq!((positive / Interval::zero()).check(), Err(Error::DivisionByZero));
+    }
+
+    #[test]
+    fn test_enclosure() {
+        let rc = Rc::new(RefCell::new(Interval::zero()));
+        let x = || Element::new_unknown(rc.clone());
+
+        // x + [2.95, 3.05] = 10
+        let relation = (x() + Interval::with_tolerance(3., 0.05)).equals(Interval::point(10.));
+        let root = *relation.solve().unwrap().roots().single().unwrap();
+        assert!(root.contains(6.95) && root.contains(7.05));
+        assert!(root.width() < 0.1 + 1e-12);
+
+        // 100 / x = [19, 21]
+        let relation = (Interval::point(100.) / x()).equals(Interval::new(19., 21.));
+        let root = *relation.solve().unwrap().roots().single().unwrap();
+        assert!(root.contains(100. / 21.) && root.contains(100. / 19.));
+
+        // [-1, 1] * x = 1
+        let relation = (Interval::new(-1., 1.) * x()).equals(Interval::one());
+        assert_eq!(relation.solve().unwrap().roots().single(), Some(&Interval::ENTIRE));
+
+        // x^2 = 2
+        let relation = (x() * x()).equals(Interval::point(2.));
+        assert_eq!(relation.solve(), Err(Error::NonLinear));
+    }
+}
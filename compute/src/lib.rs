@@ -1,4 +1,22 @@
+pub mod complex;
+pub mod dual;
 pub mod element;
 pub mod equation;
 pub mod error;
+pub mod expression;
+mod format;
+pub mod function;
+pub mod inequality;
+pub mod integer;
+pub mod interval;
+pub mod modular;
+pub mod nonlinear;
+mod polynomial;
+pub mod rational;
+pub mod relation;
+pub mod roots;
+pub mod scalar;
+pub mod system;
+pub mod tolerance;
+pub mod unit;
 pub mod unknown;
@@ -0,0 +1,246 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::error::Error;
+use crate::function::Function;
+use crate::integer::extended_gcd;
+use crate::scalar::Scalar;
+
+fn log_compute(message: String) {
+    logger::log(logger::LogStep::Compute, &message);
+}
+
+// The largest modulus for which the roots of polynomials are searched among every residue
+const MAX_SEARCHED_MODULUS: u64 = 1 << 20;
+// The largest number of roots listed for a linear congruence, more being reported like infinitely many
+const MAX_LISTED_ROOTS: i128 = 1 << 16;
+
+// A residue modulo 'N', for congruences like '7 * x + 3 ≡ 5 (mod 26)'
+// Divisions multiply by the modular inverse, dividing by a residue without one poisons every later operation
+// Residues are written on the right of the operators, as the operators with a scalar on the left need a concrete type
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Modular<const N: u64>(Option<u64>);
+
+impl<const N: u64> Modular<N> {
+    // Panics if the modulus is less than 2
+    pub fn new(value: i128) -> Self {
+        assert!(N >= 2, "the modulus must be at least 2: {}", N);
+        Self(Some(value.rem_euclid(N as i128) as u64))
+    }
+
+    pub fn modulus(&self) -> u64 {
+        N
+    }
+
+    // The residue in '[0, N)', None after a division by a residue without inverse
+    pub fn value(&self) -> Option<u64> {
+        self.0
+    }
+
+    // The residue 'y' such that 'x * y ≡ 1', if 'x' and 'N' are coprime
+    pub fn inverse(&self) -> Option<Self> {
+        let (gcd, s, _) = extended_gcd(self.0? as i128, N as i128);
+        Some(Self::new(s)).filter(|_| gcd == 1)
+    }
+
+    fn operands(self, rhs: Self) -> Option<(u128, u128)> {
+        Some((self.0? as u128, rhs.0? as u128))
+    }
+
+    fn reduced(value: Option<u128>) -> Self {
+        Self(value.map(|value| (value % N as u128) as u64))
+    }
+}
+
+impl<const N: u64> From<i32> for Modular<N> {
+    fn from(value: i32) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl<const N: u64> From<i64> for Modular<N> {
+    fn from(value: i64) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl<const N: u64> Display for Modular<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "invalid"),
+        }
+    }
+}
+
+impl<const N: u64> Add for Modular<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::reduced(self.operands(rhs).map(|(a, b)| a + b))
+    }
+}
+
+impl<const N: u64> Neg for Modular<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::reduced(self.0.map(|value| N as u128 - value as u128))
+    }
+}
+
+impl<const N: u64> Sub for Modular<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const N: u64> Mul for Modular<N> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::reduced(self.operands(rhs).map(|(a, b)| a * b))
+    }
+}
+
+impl<const N: u64> Div for Modular<N> {
+    type Output = Self;
+
+    // Dividing is multiplying by the inverse
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        match rhs.inverse() {
+            Some(inverse) => self * inverse,
+            None => Self(None),
+        }
+    }
+}
+
+impl<const N: u64> Scalar for Modular<N> {
+    fn zero() -> Self {
+        Self::new(0)
+    }
+
+    fn one() -> Self {
+        Self::new(1)
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.0.map_or(f64::NAN, |value| value as f64)
+    }
+
+    // Dividing by a residue without inverse is like dividing by 0
+    fn check(&self) -> Result<(), Error> {
+        self.0.map(|_| ()).ok_or(Error::DivisionByZero)
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value)
+            .filter(|value| value.fract() == 0. && value.abs() < i128::MAX as f64)
+            .map(|value| Self::new(value as i128))
+    }
+
+    // Residues have no real value to compute functions with
+    fn apply(&self, _function: &Function<Self>) -> Option<Self> {
+        None
+    }
+
+    // 'a * x ≡ -b' has 'gcd(a, N)' solutions if 'gcd(a, N)' divides 'b', none otherwise
+    fn linear_roots(b: &Self, a: &Self) -> Result<Vec<Self>, Error> {
+        let (Some(a), Some(b)) = (a.0, (-*b).0) else {
+            return Err(Error::DivisionByZero);
+        };
+        let (gcd, s, _) = extended_gcd(a as i128, N as i128);
+        if b as i128 % gcd != 0 {
+            log_compute(format!("Error: gcd({}, {}) = {} does not divide {}", a, N, gcd, b));
+            return Err(Error::NoSolution);
+        }
+        // Too many roots to be listed, like every residue for '0 * x ≡ 0'
+        if gcd > MAX_LISTED_ROOTS {
+            log_compute(format!("Error: {} roots modulo {}, too many to be listed", gcd, N));
+            return Err(Error::InfiniteSolutions);
+        }
+        // 'x ≡ x0 (mod N / gcd)', written as the residues modulo 'N'
+        let step = N as i128 / gcd;
+        let x0 = (s.rem_euclid(step) * (b as i128 / gcd)).rem_euclid(step);
+        Ok((0..gcd).map(|k| Self::new(x0 + k * step)).collect())
+    }
+
+    // Every residue is tried, for moduli small enough
+    fn polynomial_roots(coefficients: &[Self]) -> Result<Vec<Self>, Error> {
+        if N > MAX_SEARCHED_MODULUS {
+            log_compute(format!("Error: Modulus {} too large to search the roots of a polynomial", N));
+            return Err(Error::DegreeTooHigh);
+        }
+        let roots = (0..N)
+            .map(|x| Self::new(x as i128))
+            .filter(|x| {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(Self::zero(), |value, a| value * *x + *a)
+                    .is_zero()
+            })
+            .collect();
+        Ok(roots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::element::Element;
+    use crate::roots::Roots;
+
+    type Mod26 = Modular<26>;
+
+    #[test]
+    fn test_modular_arithmetic() {
+        let seven = Mod26::from(7);
+        assert_eq!(seven * Mod26::from(4), Mod26::from(2));
+        assert_eq!(-seven, Mod26::from(19));
+        assert_eq!(seven - Mod26::from(10), Mod26::from(23));
+        assert_eq!(seven.inverse(), Some(Mod26::from(15)));
+        assert_eq!(Mod26::from(2) / seven, Mod26::from(4));
+        assert_eq!(Mod26::from(4).inverse(), None);
+        assert_eq!((seven / Mod26::from(4)).check(), Err(Error::DivisionByZero));
+        assert_eq!(Mod26::new(-1).value(), Some(25));
+        assert_eq!(Mod26::from(30).to_string(), "4");
+    }
+
+    #[test]
+    fn test_congruences() {
+        let rc = Rc::new(RefCell::new(Mod26::zero()));
+        let x = || Element::new_unknown(rc.clone());
+
+        // 7x + 3 ≡ 5 (mod 26)
+        let relation = (x() * Mod26::from(7) + Mod26::from(3)).equals(Mod26::from(5));
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(Mod26::from(4)));
+
+        // 4x ≡ 6 (mod 26) is x ≡ 8 (mod 13), 4x ≡ 5 (mod 26) never holds
+        let relation = (x() * Mod26::from(4)).equals(Mod26::from(6));
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(vec![Mod26::from(8), Mod26::from(21)]));
+        let relation = (x() * Mod26::from(4)).equals(Mod26::from(5));
+        assert_eq!(relation.solve(), Err(Error::NoSolution));
+
+        // 2^39 * x ≡ 0 (mod 2^40) has 2^39 roots, and 0 * x ≡ 0 (mod 2^40) every residue
+        type Large = Modular<{ 1 << 40 }>;
+        assert_eq!(Large::linear_roots(&Large::zero(), &Large::new(1 << 39)), Err(Error::InfiniteSolutions));
+        assert_eq!(Large::linear_roots(&Large::zero(), &Large::zero()), Err(Error::InfiniteSolutions));
+        assert_eq!(Large::linear_roots(&Large::one(), &Large::zero()), Err(Error::NoSolution));
+        assert_eq!(Large::linear_roots(&Large::zero(), &Large::new(2)).map(|roots| roots.len()), Ok(2));
+
+        // x^2 ≡ 1 (mod 8)
+        let rc = Rc::new(RefCell::new(Modular::<8>::zero()));
+        let x = Element::new_unknown(rc);
+        let relation = (x.clone() * x).equals(Modular::<8>::one());
+        let roots: Vec<u64> = relation.solve().unwrap().roots().iter().filter_map(Modular::value).collect();
+        assert_eq!(roots, vec![1, 3, 5, 7]);
+    }
+}
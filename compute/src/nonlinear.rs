@@ -0,0 +1,393 @@
+use std::collections::BTreeMap;
+
+use crate::dual::Dual;
+use crate::element::Element;
+use crate::error::Error;
+use crate::relation::Relation;
+use crate::scalar::Scalar;
+use crate::system::gauss_jordan;
+use crate::unknown::{Unknown, UnknownId};
+
+const DEFAULT_MAX_ITERATIONS: usize = 100;
+const DEFAULT_RESIDUAL_TOLERANCE: f64 = 1e-10;
+// The smallest fraction of a step tried by the line search
+const MIN_STEP: f64 = 1e-10;
+// The decrease of the residual norm a step must bring, relatively to its length
+const SUFFICIENT_DECREASE: f64 = 1e-4;
+
+fn log_compute(message: String) {
+    logger::log(logger::LogStep::Compute, &message);
+}
+
+// Several equations sharing their unknowns, solved all at once with Newton's method from initial guesses
+// The Jacobian is computed with dual numbers, and Levenberg–Marquardt steps are taken where it is singular
+#[derive(Clone, Debug)]
+pub struct NonlinearSystem<S = f64> {
+    equations: Vec<Element<S>>,
+    guesses: BTreeMap<UnknownId, S>,
+    max_iterations: usize,
+    residual_tolerance: f64,
+}
+
+// Why the iterations stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Convergence {
+    // Every residual is under the tolerance
+    Converged,
+    // No step decreases the residuals anymore, like around a minimum that is not a root
+    Stalled,
+    MaxIterations,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NonlinearSolution {
+    convergence: Convergence,
+    iterations: usize,
+    // The norm of the residuals from the initial guesses to the last iteration
+    residual_norms: Vec<f64>,
+    // The last 'lhs - rhs' of each equation
+    residuals: Vec<f64>,
+}
+
+impl<S> Default for NonlinearSystem<S> {
+    fn default() -> Self {
+        Self {
+            equations: vec![],
+            guesses: BTreeMap::new(),
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            residual_tolerance: DEFAULT_RESIDUAL_TOLERANCE,
+        }
+    }
+}
+
+impl<S: Scalar> NonlinearSystem<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_iterations(self, max_iterations: usize) -> Self {
+        Self { max_iterations, ..self }
+    }
+
+    // The system is solved once the norm of the residuals is at most 'residual_tolerance'
+    pub fn with_residual_tolerance(self, residual_tolerance: f64) -> Self {
+        Self {
+            residual_tolerance,
+            ..self
+        }
+    }
+
+    // Adds the equation 'lhs = rhs' to the system
+    pub fn add_equation<L, R>(&mut self, lhs: L, rhs: R)
+    where
+        L: Into<Element<S>>,
+        R: Into<Element<S>>,
+    {
+        self.equations.push(lhs.into() - rhs.into());
+    }
+
+    pub fn add_relation(&mut self, relation: Relation<S>) {
+        self.add_equation(relation.lhs().clone(), relation.rhs().clone());
+    }
+
+    // The unknowns without a guess start from their current value
+    // 'x' is an unknown, or an element linear in a single unknown like an unknown with a unit, starting at 'guess'
+    pub fn set_initial_guess<X>(&mut self, x: &X, guess: S) -> Result<(), Error>
+    where
+        X: Clone + Into<Element<S>>,
+    {
+        let element: Element<S> = x.clone().into();
+        element.error()?;
+        let (id, a) = match Vec::from_iter(element.unknown_ids()).as_slice() {
+            [] => return Err(Error::NoUnkown),
+            [id] => {
+                let coefficients = element.numerator().linear_coefficients().filter(|_| element.is_algebraic());
+                match coefficients.and_then(|coefficients| coefficients.get(id).cloned()) {
+                    Some(a) if element.denominator().is_constant() => (*id, a),
+                    _ => return Err(Error::NonLinear),
+                }
+            },
+            _ => return Err(Error::SeveralUnknown),
+        };
+        // 'guess = (a * x + b) / d'
+        let (b, d) = (element.numerator().constant_term(), element.denominator().constant_term());
+        let value = (guess * d - b) / a;
+        value.check()?;
+        self.guesses.insert(id, value);
+        Ok(())
+    }
+
+    // Iterates from the initial guesses and writes the value reached by every unknown, even without convergence
+    pub fn solve(&self) -> Result<NonlinearSolution, Error> {
+        let mut unknowns: BTreeMap<UnknownId, Unknown<S>> = BTreeMap::new();
+        for equation in &self.equations {
+            equation.error()?;
+            let ids = equation.unknown_ids();
+            for x in equation.unknowns().filter(|x| ids.contains(&x.id())) {
+                unknowns.entry(x.id()).or_insert_with(|| x.clone());
+            }
+        }
+        if unknowns.is_empty() {
+            log_compute("Error: No unknown in the system".to_string());
+            return Err(Error::NoUnkown);
+        }
+
+        let mut x = unknowns
+            .values()
+            .map(|x| match self.guesses.get(&x.id()) {
+                Some(guess) => guess.to_f64(),
+                None => x.unknown.borrow().to_f64(),
+            })
+            .collect::<Option<Vec<f64>>>()
+            .ok_or(Error::NotRepresentable)?;
+        let ids: Vec<UnknownId> = unknowns.keys().cloned().collect();
+        log_compute(format!(
+            "Solving {} nonlinear equations with {} unknowns from {:?}",
+            self.equations.len(),
+            ids.len(),
+            x
+        ));
+
+        let Some((mut residuals, mut jacobian)) = self.linearize(&ids, &x) else {
+            log_compute("Error: Initial guesses out of the domain of a function".to_string());
+            return Err(Error::OutOfDomain);
+        };
+        let mut residual_norms = vec![norm(&residuals)];
+        let mut iterations = 0;
+        let convergence = loop {
+            let residual_norm = residual_norms[iterations];
+            if residual_norm <= self.residual_tolerance {
+                break Convergence::Converged;
+            }
+            if iterations == self.max_iterations {
+                break Convergence::MaxIterations;
+            }
+            let step = newton_step(&jacobian, &residuals).or_else(|| levenberg_marquardt_step(&jacobian, &residuals));
+            let Some(step) = step else {
+                break Convergence::Stalled;
+            };
+
+            // Backtracks until the residuals decrease enough
+            let mut fraction = 1.;
+            let next = loop {
+                if fraction < MIN_STEP {
+                    break None;
+                }
+                let candidate: Vec<f64> = x.iter().zip(&step).map(|(x, dx)| x + fraction * dx).collect();
+                if let Some((residuals, jacobian)) = self.linearize(&ids, &candidate) {
+                    if norm(&residuals) <= residual_norm * (1. - SUFFICIENT_DECREASE * fraction) {
+                        break Some((candidate, residuals, jacobian));
+                    }
+                }
+                fraction /= 2.;
+            };
+            let Some(next) = next else {
+                break Convergence::Stalled;
+            };
+            (x, residuals, jacobian) = next;
+            iterations += 1;
+            residual_norms.push(norm(&residuals));
+        };
+        log_compute(format!(
+            "{:?} after {} iterations, residual norm {:e}",
+            convergence, iterations, residual_norms[iterations]
+        ));
+
+        for (x, value) in unknowns.values().zip(&x) {
+            *x.unknown.borrow_mut() = S::from_f64(*value).ok_or(Error::NotRepresentable)?;
+        }
+
+        Ok(NonlinearSolution {
+            convergence,
+            iterations,
+            residual_norms,
+            residuals,
+        })
+    }
+
+    // The residuals at 'x' and their derivatives, None out of the domain of a function
+    fn linearize(&self, ids: &[UnknownId], x: &[f64]) -> Option<(Vec<f64>, Vec<Vec<f64>>)> {
+        let values: BTreeMap<UnknownId, Dual> = ids
+            .iter()
+            .zip(x)
+            .enumerate()
+            .map(|(index, (id, value))| (*id, Dual::variable(*value, index)))
+            .collect();
+        let convert = |a: &S| Dual::constant(a.to_f64().unwrap_or(f64::NAN));
+        self.equations
+            .iter()
+            .map(|equation| {
+                let residual = equation.evaluate_as(&values, &convert)?;
+                let derivatives: Vec<f64> = (0..ids.len()).map(|index| residual.derivative(index)).collect();
+                Some((residual.value(), derivatives))
+                    .filter(|(value, derivatives)| value.is_finite() && derivatives.iter().all(|d| d.is_finite()))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|rows| rows.into_iter().unzip())
+    }
+}
+
+fn norm(values: &[f64]) -> f64 {
+    values.iter().map(|value| value * value).sum::<f64>().sqrt()
+}
+
+// Solves 'J * dx = -F', only for a square and regular Jacobian
+fn newton_step(jacobian: &[Vec<f64>], residuals: &[f64]) -> Option<Vec<f64>> {
+    let columns = jacobian.first()?.len();
+    if jacobian.len() != columns {
+        return None;
+    }
+    let mut rows: Vec<Vec<f64>> = jacobian
+        .iter()
+        .zip(residuals)
+        .map(|(row, residual)| row.iter().cloned().chain([-residual]).collect())
+        .collect();
+    gauss_jordan(&mut rows, columns).ok()
+}
+
+// Solves '(J^T * J + lambda * I) * dx = -J^T * F', a step between Newton's and the steepest descent
+fn levenberg_marquardt_step(jacobian: &[Vec<f64>], residuals: &[f64]) -> Option<Vec<f64>> {
+    log_compute("Singular Jacobian, taking a Levenberg–Marquardt step".to_string());
+    let columns = jacobian.first()?.len();
+    let mut rows: Vec<Vec<f64>> = (0..columns)
+        .map(|i| {
+            (0..columns)
+                .map(|j| jacobian.iter().map(|row| row[i] * row[j]).sum())
+                .chain([-jacobian.iter().zip(residuals).map(|(row, residual)| row[i] * residual).sum::<f64>()])
+                .collect()
+        })
+        .collect();
+    let largest = (0..columns).fold(0., |max: f64, i| max.max(rows[i][i]));
+    let lambda = if largest > 0. { largest * 1e-3 } else { 1. };
+    (0..columns).for_each(|i| rows[i][i] += lambda);
+    gauss_jordan(&mut rows, columns).ok()
+}
+
+impl NonlinearSolution {
+    pub fn convergence(&self) -> Convergence {
+        self.convergence
+    }
+
+    pub fn converged(&self) -> bool {
+        self.convergence == Convergence::Converged
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    pub fn residual_norms(&self) -> &[f64] {
+        &self.residual_norms
+    }
+
+    pub fn residual_norm(&self) -> f64 {
+        self.residual_norms[self.iterations]
+    }
+
+    pub fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn check(rc: &Rc<RefCell<f64>>, value: f64) {
+        assert!((*rc.borrow() - value).abs() < 1e-9, "{} != {}", rc.borrow(), value);
+    }
+
+    #[test]
+    fn test_solve_nonlinear_system() {
+        let (x, y) = (Rc::new(RefCell::new(1.)), Rc::new(RefCell::new(0.5)));
+        let ex = || Element::new_unknown(x.clone());
+        let ey = || Element::new_unknown(y.clone());
+
+        // The intersection of a circle and a line
+        let mut system = NonlinearSystem::new();
+        system.add_equation(ex() * ex() + ey() * ey(), 4.);
+        system.add_relation(ey().equals(ex()));
+        let solution = system.solve().unwrap();
+        assert!(solution.converged());
+        assert_eq!(solution.residual_norms().len(), solution.iterations() + 1);
+        assert!(solution.residual_norm() <= DEFAULT_RESIDUAL_TOLERANCE);
+        check(&x, 2f64.sqrt());
+        check(&y, 2f64.sqrt());
+
+        // Functions are differentiated too
+        let mut system = NonlinearSystem::new();
+        system.add_equation(ex().exp(), ey());
+        system.add_equation(ex() + ey(), 2.);
+        let solution = system.solve().unwrap();
+        assert!(solution.converged());
+        assert!(solution.residuals().iter().all(|residual| residual.abs() <= DEFAULT_RESIDUAL_TOLERANCE));
+        check(&y, x.borrow().exp());
+        check(&x, 2. - *y.borrow());
+
+        // More equations than unknowns, with a singular Jacobian at the start
+        *x.borrow_mut() = 0.;
+        *y.borrow_mut() = 0.;
+        let mut system = NonlinearSystem::new();
+        system.add_equation(ex() * ey(), 2.);
+        system.add_equation(ex() + ey(), 3.);
+        system.add_equation(ex() - ey(), 1.);
+        assert!(system.solve().unwrap().converged());
+        check(&x, 2.);
+        check(&y, 1.);
+    }
+
+    #[test]
+    fn test_initial_guesses() {
+        let x = crate::equation::EquationElement::<f64>::unknown();
+        let mut system = NonlinearSystem::new();
+        system.add_equation(x.clone() * x.clone(), 4.);
+        system.set_initial_guess(&x, -1.).unwrap();
+        system.solve().unwrap();
+        assert!((x.get_unknown_value().unwrap() + 2.).abs() < 1e-9);
+        system.set_initial_guess(&x, 3.).unwrap();
+        system.solve().unwrap();
+        assert!((x.get_unknown_value().unwrap() - 2.).abs() < 1e-9);
+
+        let mut system = NonlinearSystem::new();
+        system.add_equation(x.clone().ln(), 0.);
+        system.set_initial_guess(&x, -1.).unwrap();
+        assert_eq!(system.solve(), Err(Error::OutOfDomain));
+        assert_eq!(NonlinearSystem::<f64>::new().solve(), Err(Error::NoUnkown));
+
+        // A plain element, and an element linear in its unknown whose value starts at the guess
+        let rc = Rc::new(RefCell::new(0.));
+        let y = Element::new_unknown(rc.clone());
+        let mut system = NonlinearSystem::new();
+        system.add_equation(y.clone() * y.clone(), 4.);
+        system.set_initial_guess(&y, -1.).unwrap();
+        system.solve().unwrap();
+        check(&rc, -2.);
+        system.set_initial_guess(&(y.clone() * 2. + 1.), 5.).unwrap();
+        system.solve().unwrap();
+        check(&rc, 2.);
+        assert_eq!(system.set_initial_guess(&(y.clone() * y.clone()), 1.), Err(Error::NonLinear));
+        assert_eq!(system.set_initial_guess(&Element::new_known(1.), 1.), Err(Error::NoUnkown));
+    }
+
+    #[test]
+    fn test_no_convergence() {
+        let rc = Rc::new(RefCell::new(1.));
+        let x = || Element::new_unknown(rc.clone());
+
+        // 'x^2 + 1' is at least 1
+        let mut system = NonlinearSystem::new();
+        system.add_equation(x() * x() + 1., 0.);
+        let solution = system.solve().unwrap();
+        assert!(!solution.converged());
+        assert!((solution.residual_norm() - 1.).abs() < 1e-6);
+        assert!(solution.residual_norms().windows(2).all(|norms| norms[1] < norms[0]));
+
+        *rc.borrow_mut() = 10.;
+        let solution = system.with_max_iterations(2).solve().unwrap();
+        assert_eq!(solution.convergence(), Convergence::MaxIterations);
+        assert_eq!(solution.iterations(), 2);
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::error::Error;
+use crate::scalar::Scalar;
+use crate::unknown::UnknownId;
+
+// A product of unknowns like 'x^2 * y', stored as (unknown, power) sorted by unknown
+pub(crate) type Monomial = Vec<(UnknownId, u32)>;
+
+fn multiply_monomials(first: &Monomial, second: &Monomial) -> Monomial {
+    let mut powers: BTreeMap<UnknownId, u32> = first.iter().cloned().collect();
+    for (x, power) in second {
+        *powers.entry(*x).or_insert(0) += power;
+    }
+    powers.into_iter().collect()
+}
+
+fn monomial_degree(monomial: &Monomial) -> u32 {
+    monomial.iter().map(|(_, power)| power).sum()
+}
+
+fn power<S: Scalar>(x: &S, power: u32) -> S {
+    (0..power).fold(S::one(), |product, _| product * x.clone())
+}
+
+// Represents a polynomial like 'a * x^2 + b * x * y + c', null terms are never stored
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Polynomial<S> {
+    terms: BTreeMap<Monomial, S>,
+}
+
+impl<S> Default for Polynomial<S> {
+    fn default() -> Self {
+        Self {
+            terms: BTreeMap::new(),
+        }
+    }
+}
+
+impl<S: Scalar> Polynomial<S> {
+    pub(crate) fn constant(constant: S) -> Self {
+        Self::default().with_term(vec![], constant)
+    }
+
+    pub(crate) fn unknown(x: UnknownId) -> Self {
+        Self::default().with_term(vec![(x, 1)], S::one())
+    }
+
+    fn with_term(mut self, monomial: Monomial, a: S) -> Self {
+        let entry = self.terms.entry(monomial).or_insert_with(S::zero);
+        *entry = entry.clone() + a;
+        if entry.is_zero() {
+            self.terms.retain(|_, a| !a.is_zero());
+        }
+        self
+    }
+
+    pub(crate) fn degree(&self) -> u32 {
+        self.terms.keys().map(monomial_degree).max().unwrap_or(0)
+    }
+
+    pub(crate) fn constant_term(&self) -> S {
+        self.terms.get(&vec![]).cloned().unwrap_or_else(S::zero)
+    }
+
+    // The terms with the highest degree first, as they are written
+    pub(crate) fn terms(&self) -> Vec<(&Monomial, &S)> {
+        let mut terms: Vec<(&Monomial, &S)> = self.terms.iter().collect();
+        terms.sort_by_key(|(monomial, _)| std::cmp::Reverse(monomial_degree(monomial)));
+        terms
+    }
+
+    // The size of the largest coefficient
+    pub(crate) fn magnitude(&self) -> f64 {
+        self.terms.values().fold(0., |max, a| max.max(a.magnitude()))
+    }
+
+    // The polynomial without the terms whose coefficient is negligible
+    pub(crate) fn truncated<F>(mut self, negligible: F) -> Self
+    where
+        F: Fn(&S) -> bool,
+    {
+        self.terms.retain(|_, a| !negligible(a));
+        self
+    }
+
+    // Fails if a coefficient results from an invalid operation
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        self.terms.values().try_for_each(Scalar::check)
+    }
+
+    pub(crate) fn is_constant(&self) -> bool {
+        self.degree() == 0
+    }
+
+    // Unknowns with at least one non null term
+    pub(crate) fn unknowns(&self) -> BTreeSet<UnknownId> {
+        self.terms
+            .keys()
+            .flatten()
+            .map(|(x, _)| *x)
+            .collect()
+    }
+
+    pub(crate) fn evaluate(&self, values: &BTreeMap<UnknownId, S>) -> S {
+        self.evaluate_as(values, &S::clone)
+    }
+
+    // The value computed with another scalar, the coefficients being converted by 'convert'
+    pub(crate) fn evaluate_as<T, F>(&self, values: &BTreeMap<UnknownId, T>, convert: &F) -> T
+    where
+        T: Scalar,
+        F: Fn(&S) -> T,
+    {
+        self.terms
+            .iter()
+            .map(|(monomial, a)| {
+                monomial
+                    .iter()
+                    .fold(convert(a), |product, (x, n)| product * power(&values[x], *n))
+            })
+            .fold(T::zero(), |sum, term| sum + term)
+    }
+
+    // Whether the polynomial is null for the given values, relatively to the size of its terms
+    pub(crate) fn vanishes_at(&self, values: &BTreeMap<UnknownId, S>) -> bool {
+        let magnitude: f64 = self.terms
+            .iter()
+            .map(|(monomial, a)| {
+                monomial
+                    .iter()
+                    .fold(a.magnitude(), |product, (x, n)| product * values[x].magnitude().powi(*n as i32))
+            })
+            .sum();
+        self.evaluate(values).approx_eq(&S::zero(), magnitude)
+    }
+
+    // The coefficient of each unknown if the polynomial is 'a1 * x1 + a2 * x2 + ... + b'
+    pub(crate) fn linear_coefficients(&self) -> Option<BTreeMap<UnknownId, S>> {
+        self.terms
+            .iter()
+            .filter(|(monomial, _)| !monomial.is_empty())
+            .map(|(monomial, a)| match monomial.as_slice() {
+                [(x, 1)] => Some((*x, a.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // The coefficients of 'x^0, x^1, ...' if 'x' is the only unknown in the polynomial
+    pub(crate) fn univariate_coefficients(&self, x: UnknownId) -> Option<Vec<S>> {
+        let mut coefficients = vec![S::zero(); self.degree() as usize + 1];
+        for (monomial, a) in &self.terms {
+            let power = match monomial.as_slice() {
+                [] => 0,
+                [(y, power)] if *y == x => *power as usize,
+                _ => return None,
+            };
+            coefficients[power] = coefficients[power].clone() + a.clone();
+        }
+        Some(coefficients)
+    }
+}
+
+impl<S: Scalar> Add for Polynomial<S> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        rhs.terms
+            .into_iter()
+            .fold(self, |sum, (monomial, a)| sum.with_term(monomial, a))
+    }
+}
+
+impl<S: Scalar> Neg for Polynomial<S> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self * -S::one()
+    }
+}
+
+impl<S: Scalar> Sub for Polynomial<S> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<S: Scalar> Mul for Polynomial<S> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut product = Self::default();
+        for (monomial1, a1) in &self.terms {
+            for (monomial2, a2) in &rhs.terms {
+                product = product.with_term(multiply_monomials(monomial1, monomial2), a1.clone() * a2.clone());
+            }
+        }
+        product
+    }
+}
+
+impl<S: Scalar> Mul<S> for Polynomial<S> {
+    type Output = Self;
+
+    fn mul(self, rhs: S) -> Self {
+        self.terms
+            .into_iter()
+            .fold(Self::default(), |product, (monomial, a)| product.with_term(monomial, a * rhs.clone()))
+    }
+}
+
+impl<S: Scalar> Div<S> for Polynomial<S> {
+    type Output = Self;
+
+    fn div(self, rhs: S) -> Self {
+        self.terms
+            .into_iter()
+            .fold(Self::default(), |quotient, (monomial, a)| quotient.with_term(monomial, a / rhs.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_polynomial() {
+        let (x, y) = (1, 2);
+
+        // (x + 1) * (x - 1) = x^2 - 1
+        let product = (Polynomial::unknown(x) + Polynomial::constant(1.))
+            * (Polynomial::unknown(x) - Polynomial::constant(1.));
+        assert_eq!(product.degree(), 2);
+        assert_eq!(product.univariate_coefficients(x), Some(vec![-1., 0., 1.]));
+        assert_eq!(product.linear_coefficients(), None);
+        assert_eq!(product.evaluate(&BTreeMap::from([(x, 3.)])), 8.);
+        assert!(product.vanishes_at(&BTreeMap::from([(x, -1.)])));
+        assert!(!product.vanishes_at(&BTreeMap::from([(x, 0.)])));
+
+        // (x + y) - y = x
+        let sub = Polynomial::<f64>::unknown(x) + Polynomial::unknown(y) - Polynomial::unknown(y);
+        assert_eq!(sub, Polynomial::unknown(x));
+        assert_eq!(sub.unknowns(), BTreeSet::from([x]));
+
+        // 2x + 3y + 4
+        let linear = Polynomial::unknown(x) * 2. + Polynomial::unknown(y) * 3. + Polynomial::constant(4.);
+        assert_eq!(linear.linear_coefficients(), Some(BTreeMap::from([(x, 2.), (y, 3.)])));
+        assert_eq!(linear.constant_term(), 4.);
+        assert_eq!(linear.univariate_coefficients(x), None);
+
+        // x * y is not univariate
+        let product = Polynomial::<f64>::unknown(x) * Polynomial::unknown(y);
+        assert_eq!(product.degree(), 2);
+        assert!(!product.is_constant());
+        assert_eq!(product.univariate_coefficients(x), None);
+    }
+}
@@ -0,0 +1,420 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::complex::Complex;
+use crate::error::Error;
+use crate::roots;
+use crate::scalar::{impl_scalar_lhs_ops, Scalar};
+
+// Denominators of the fractions tried when recovering an exact root from its f64 approximation
+const MAX_ROOT_DENOMINATOR: f64 = 1e12;
+// Relative distance between an exact root and its f64 approximation, that multiple roots make coarse
+const ROOT_DISTANCE: f64 = 1e-6;
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// An exact fraction 'numerator / denominator', kept irreducible with a positive denominator
+// A null denominator marks the result of an invalid operation, that poisons every later operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numerator: 0, denominator: 1 };
+    pub const ONE: Rational = Rational { numerator: 1, denominator: 1 };
+    const OVERFLOW: Rational = Rational { numerator: 1, denominator: 0 };
+    const DIVISION_BY_ZERO: Rational = Rational { numerator: 0, denominator: 0 };
+
+    // Panics if the denominator is null, like an integer division
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "attempt to create a rational with a null denominator");
+        Self::reduced(Some(numerator), Some(denominator))
+    }
+
+    pub fn integer(value: i128) -> Self {
+        Self::new(value, 1)
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.denominator != 0
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    // The exact value of a finite float, if it fits
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        if value == 0. {
+            return Some(Self::integer(0));
+        }
+        let bits = value.to_bits();
+        let sign = if bits >> 63 == 0 { 1 } else { -1 };
+        let exponent = ((bits >> 52) & 0x7ff) as i32;
+        let mut mantissa = (bits & ((1 << 52) - 1)) as i128;
+        let mut exponent = if exponent == 0 {
+            -1074
+        } else {
+            mantissa |= 1 << 52;
+            exponent - 1075
+        };
+        while mantissa % 2 == 0 {
+            mantissa /= 2;
+            exponent += 1;
+        }
+
+        if exponent.abs() > 126 {
+            return None;
+        }
+        let rational = if exponent >= 0 {
+            Self::reduced(mantissa.checked_mul(1 << exponent), Some(1))
+        } else {
+            Self::new(mantissa, 1 << -exponent)
+        };
+        Some(rational * Self::integer(sign)).filter(Rational::is_valid)
+    }
+
+    // Closest fractions to 'value' with growing denominators, from its continued fraction
+    fn convergents(value: f64) -> Vec<Self> {
+        let mut convergents = vec![];
+        let (mut h, mut previous_h) = (1i128, 0i128);
+        let (mut k, mut previous_k) = (0i128, 1i128);
+        let mut x = value;
+        while convergents.len() < 64 {
+            let a = x.floor();
+            if a.abs() >= 1e36 {
+                break;
+            }
+            let a = a as i128;
+            let next_h = a.checked_mul(h).and_then(|ah| ah.checked_add(previous_h));
+            let next_k = a.checked_mul(k).and_then(|ak| ak.checked_add(previous_k));
+            match (next_h, next_k) {
+                (Some(next_h), Some(next_k)) if next_k as f64 <= MAX_ROOT_DENOMINATOR => {
+                    (h, previous_h) = (next_h, h);
+                    (k, previous_k) = (next_k, k);
+                },
+                _ => break,
+            }
+            convergents.push(Self::new(h, k));
+            if x == a as f64 {
+                break;
+            }
+            x = 1. / (x - a as f64);
+        }
+        convergents
+    }
+
+    fn reduced(numerator: Option<i128>, denominator: Option<i128>) -> Self {
+        let (Some(numerator), Some(denominator)) = (numerator, denominator) else {
+            return Self::OVERFLOW;
+        };
+        let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs());
+        let divisor = match i128::try_from(divisor) {
+            Ok(divisor) if divisor != 0 => divisor,
+            _ => return Self::OVERFLOW,
+        };
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        if denominator < 0 {
+            Self::reduced(numerator.checked_neg(), denominator.checked_neg())
+        } else {
+            Self {
+                numerator,
+                denominator,
+            }
+        }
+    }
+
+    fn invalid(&self, rhs: &Self) -> Option<Self> {
+        [*self, *rhs].into_iter().find(|x| !x.is_valid())
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(value: i32) -> Self {
+        Self::integer(value.into())
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::integer(value.into())
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl From<Rational> for f64 {
+    fn from(value: Rational) -> Self {
+        value.to_f64()
+    }
+}
+
+// Compares 'a / b' and 'c / d' with positive denominators, through their continued fractions to avoid overflows
+fn compare(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let (q1, r1) = (a.div_euclid(b), a.rem_euclid(b));
+    let (q2, r2) = (c.div_euclid(d), c.rem_euclid(d));
+    match (q1.cmp(&q2), r1, r2) {
+        (Ordering::Equal, 0, 0) => Ordering::Equal,
+        (Ordering::Equal, 0, _) => Ordering::Less,
+        (Ordering::Equal, _, 0) => Ordering::Greater,
+        // r1 / b < r2 / d if b / r1 > d / r2
+        (Ordering::Equal, _, _) => compare(b, r1, d, r2).reverse(),
+        (ordering, _, _) => ordering,
+    }
+}
+
+// Results of invalid operations can't be compared
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        if self.invalid(rhs).is_some() {
+            return None;
+        }
+        Some(compare(self.numerator, self.denominator, rhs.numerator, rhs.denominator))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::OVERFLOW => write!(f, "overflow"),
+            Self::DIVISION_BY_ZERO => write!(f, "0/0"),
+            _ if self.denominator == 1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if let Some(invalid) = self.invalid(&rhs) {
+            return invalid;
+        }
+        let divisor = gcd(self.denominator as u128, rhs.denominator as u128) as i128;
+        let (factor1, factor2) = (rhs.denominator / divisor, self.denominator / divisor);
+        let numerator = self
+            .numerator
+            .checked_mul(factor1)
+            .zip(rhs.numerator.checked_mul(factor2))
+            .and_then(|(n1, n2)| n1.checked_add(n2));
+        Self::reduced(numerator, self.denominator.checked_mul(factor1))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        if !self.is_valid() {
+            return self;
+        }
+        Self::reduced(self.numerator.checked_neg(), Some(self.denominator))
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if let Some(invalid) = self.invalid(&rhs) {
+            return invalid;
+        }
+        // Simplifying crosswise first keeps the products as small as possible
+        let divisor1 = gcd(self.numerator.unsigned_abs(), rhs.denominator as u128).max(1) as i128;
+        let divisor2 = gcd(rhs.numerator.unsigned_abs(), self.denominator as u128).max(1) as i128;
+        Self::reduced(
+            (self.numerator / divisor1).checked_mul(rhs.numerator / divisor2),
+            (self.denominator / divisor2).checked_mul(rhs.denominator / divisor1),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if let Some(invalid) = self.invalid(&rhs) {
+            return invalid;
+        }
+        if rhs.numerator == 0 {
+            return Self::DIVISION_BY_ZERO;
+        }
+        self * Self::reduced(Some(rhs.denominator), Some(rhs.numerator))
+    }
+}
+
+impl Scalar for Rational {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.to_f64().abs()
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        match *self {
+            Self::OVERFLOW => Err(Error::Overflow),
+            Self::DIVISION_BY_ZERO => Err(Error::DivisionByZero),
+            _ => Ok(()),
+        }
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(Rational::to_f64(self))
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Rational::from_f64(value)
+    }
+
+    // The real roots are approximated as f64, then each one must be a fraction cancelling the polynomial exactly
+    // The irrational ones are left to 'complex_roots'
+    fn polynomial_roots(coefficients: &[Self]) -> Result<Vec<Self>, Error> {
+        let mut roots: Vec<Self> = vec![];
+        for root in real_approximations(coefficients).filter_map(|x| rational_root(coefficients, x)) {
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        Ok(roots)
+    }
+
+    // The roots that are not fractions, real or not
+    fn complex_roots(coefficients: &[Self]) -> Vec<Complex> {
+        let approximations: Vec<Complex> = coefficients.iter().map(|a| Complex::from(a.to_f64())).collect();
+        let irrational = real_approximations(coefficients)
+            .filter(|x| rational_root(coefficients, *x).is_none())
+            .map(Complex::from);
+        irrational
+            .chain(roots::complex_roots(&approximations).into_iter().filter(|root| !root.is_real()))
+            .collect()
+    }
+}
+
+fn real_approximations(coefficients: &[Rational]) -> impl Iterator<Item = f64> {
+    let approximations: Vec<f64> = coefficients.iter().map(Rational::to_f64).collect();
+    roots::real_roots(&approximations).into_iter()
+}
+
+// The fraction close to 'approximation' cancelling the polynomial, if there is one
+// The first convergents are too far to stand for the approximation, even when they are other roots
+fn rational_root(coefficients: &[Rational], approximation: f64) -> Option<Rational> {
+    let close = |x: &Rational| (x.to_f64() - approximation).abs() <= ROOT_DISTANCE * approximation.abs().max(1.);
+    Rational::convergents(approximation).into_iter().filter(close).find(|x| {
+        let value = coefficients
+            .iter()
+            .rev()
+            .fold(Rational::zero(), |value, a| value * *x + *a);
+        value.is_zero()
+    })
+}
+
+impl_scalar_lhs_ops!(Rational);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::element::Element;
+    use crate::roots::Roots;
+
+    #[test]
+    fn test_rational_arithmetic() {
+        assert_eq!(Rational::new(2, -4), Rational::new(-1, 2));
+        assert_eq!(Rational::new(1, 3) + Rational::new(1, 6), Rational::new(1, 2));
+        assert_eq!(Rational::new(1, 3) - Rational::new(1, 2), Rational::new(-1, 6));
+        assert_eq!(Rational::new(2, 3) * Rational::new(9, 4), Rational::new(3, 2));
+        assert_eq!(Rational::new(2, 3) / Rational::new(-4, 9), Rational::new(-3, 2));
+        assert_eq!(Rational::from(3).to_f64(), 3.);
+        assert_eq!(f64::from(Rational::new(1, 4)), 0.25);
+        assert_eq!(Rational::from_f64(0.375), Some(Rational::new(3, 8)));
+        assert_eq!(Rational::from_f64(-6.), Some(Rational::from(-6)));
+        assert_eq!(Rational::from_f64(f64::NAN), None);
+
+        assert!(Rational::new(1, 3) < Rational::new(1, 2));
+        assert!(Rational::new(-1, 2) < Rational::new(-1, 3));
+        assert!(Rational::new(i128::MAX, i128::MAX - 1) < Rational::new(i128::MAX - 1, i128::MAX - 2));
+        assert_eq!(Rational::new(2, 4).partial_cmp(&Rational::new(1, 2)), Some(Ordering::Equal));
+        assert_eq!((Rational::ONE / Rational::ZERO).partial_cmp(&Rational::ONE), None);
+    }
+
+    #[test]
+    fn test_rational_errors() {
+        let big = Rational::integer(i128::MAX);
+        assert_eq!((big + Rational::one()).check(), Err(Error::Overflow));
+        assert_eq!((big * Rational::from(2)).check(), Err(Error::Overflow));
+        assert_eq!((-Rational::integer(i128::MIN)).check(), Err(Error::Overflow));
+        assert_eq!((Rational::one() / Rational::zero()).check(), Err(Error::DivisionByZero));
+        assert_eq!((big * Rational::from(2) - big).check(), Err(Error::Overflow));
+        assert_eq!((big - Rational::one()).check(), Ok(()));
+    }
+
+    #[test]
+    fn test_exact_solutions() {
+        let rc = Rc::new(RefCell::new(Rational::zero()));
+        let x = || Element::new_unknown(rc.clone());
+
+        // x / 3 = 1 / 7
+        let relation = (x() / Rational::from(3)).equals(Rational::new(1, 7));
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(Rational::new(3, 7)));
+
+        // 9x^2 = 4
+        let relation = (Rational::from(9) * x() * x()).equals(Rational::from(4));
+        let roots = Roots::from(vec![Rational::new(-2, 3), Rational::new(2, 3)]);
+        assert_eq!(relation.solve().unwrap().roots(), &roots);
+
+        // (x - 1)(x^2 - 2) = 0, whose irrational roots are only approximated
+        let relation = ((x() - Rational::ONE) * (x() * x() - Rational::from(2))).equals(Rational::ZERO);
+        let solution = relation.solve().unwrap();
+        assert_eq!(solution.roots(), &Roots::from(Rational::ONE));
+        let mut irrational: Vec<f64> = solution.complex_roots().iter().map(|root| root.re()).collect();
+        irrational.sort_by(f64::total_cmp);
+        assert_eq!(irrational.len(), 2);
+        assert!((irrational[1] - 2f64.sqrt()).abs() < 1e-12 && (irrational[0] + 2f64.sqrt()).abs() < 1e-12);
+
+        // x * 2^100 = 2^100 * 2^100
+        let big = Rational::integer(1 << 100);
+        let relation = (x() * big).equals(Element::new_known(big) * big);
+        assert_eq!(relation.solve(), Err(Error::Overflow));
+    }
+}
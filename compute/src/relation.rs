@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use crate::complex::Complex;
+use crate::dual::Dual;
+use crate::element::Element;
+use crate::error::Error;
+use crate::expression::Expression;
+use crate::format::{Formula, Names, Notation};
+use crate::roots::{self, Roots};
+use crate::scalar::Scalar;
+use crate::tolerance::Tolerance;
+use crate::unknown::{Unknown, UnknownId};
+
+fn log_compute(message: String) {
+    logger::log(logger::LogStep::Compute, &message);
+}
+
+// An equation 'lhs = rhs', only solved when asked to
+#[derive(Clone, Debug)]
+pub struct Relation<S = f64> {
+    lhs: Element<S>,
+    rhs: Element<S>,
+    // The trees the elements were lowered from, if written as expressions
+    expressions: Option<(Expression<S>, Expression<S>)>,
+    tolerance: Tolerance,
+}
+
+// How the roots of a relation were found
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    // Closed forms or numeric roots of a polynomial
+    Polynomial,
+    // Inversion of the function containing the unknown, like 'exp(x) = 5' giving 'x = ln(5)'
+    Analytic,
+    // Brent's method, from the current value of the unknown
+    Brent,
+}
+
+// The roots found for the unknown of a relation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution<S = f64> {
+    unknown: Unknown<S>,
+    roots: Roots<S>,
+    method: Method,
+    iterations: usize,
+    // The largest '|lhs - rhs|' among the roots
+    residual: f64,
+    // The roots of a polynomial that the scalar can't represent, like the complex roots of a real polynomial
+    complex_roots: Vec<Complex>,
+}
+
+impl<S: Scalar> Relation<S> {
+    pub fn new<L, R>(lhs: L, rhs: R) -> Self
+    where
+        L: Into<Element<S>>,
+        R: Into<Element<S>>,
+    {
+        Self {
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+            expressions: None,
+            tolerance: S::default_tolerance(),
+        }
+    }
+
+    pub fn from_expressions(lhs: Expression<S>, rhs: Expression<S>) -> Self {
+        Self {
+            lhs: lhs.lower(),
+            rhs: rhs.lower(),
+            expressions: Some((lhs, rhs)),
+            tolerance: S::default_tolerance(),
+        }
+    }
+
+    // The tolerance under which the coefficients left by 'lhs - rhs' are taken as 0
+    pub fn with_tolerance(self, tolerance: Tolerance) -> Self {
+        Self { tolerance, ..self }
+    }
+
+    pub fn tolerance(&self) -> Tolerance {
+        self.tolerance
+    }
+
+    pub fn lhs(&self) -> &Element<S> {
+        &self.lhs
+    }
+
+    pub fn rhs(&self) -> &Element<S> {
+        &self.rhs
+    }
+
+    pub fn expressions(&self) -> Option<(&Expression<S>, &Expression<S>)> {
+        self.expressions.as_ref().map(|(lhs, rhs)| (lhs, rhs))
+    }
+
+    pub fn to_latex(&self) -> String {
+        self.formula().write(Notation::Latex)
+    }
+
+    pub fn to_mathml(&self) -> String {
+        self.formula().write(Notation::MathMl)
+    }
+
+    // Written as the expressions if there are some, else as the elements
+    fn formula(&self) -> Formula {
+        let (lhs, rhs) = match &self.expressions {
+            Some((lhs, rhs)) => {
+                let names = Names::of_expressions(&[lhs, rhs]);
+                (lhs.formula(&names), rhs.formula(&names))
+            },
+            None => {
+                let names = Names::of(&[&self.lhs, &self.rhs]);
+                (self.lhs.formula(&names), self.rhs.formula(&names))
+            },
+        };
+        Formula::Relation(Box::new(lhs), "=", Box::new(rhs))
+    }
+
+    // The derivatives of 'root' with respect to the knowns lowered as unknowns by 'EquationElement::differentiated'
+    // The other unknown is the one 'root' is a value of
+    pub fn root_derivatives(&self, root: &S) -> Result<Dual, Error> {
+        let unknowns: BTreeMap<UnknownId, &Unknown<S>> =
+            self.lhs.unknowns().chain(self.rhs.unknowns()).map(|x| (x.id(), x)).collect();
+        let mut solved = unknowns.values().filter(|x| x.parameter.is_none()).map(|x| x.id());
+        let solved = match (solved.next(), solved.next()) {
+            (Some(id), None) => id,
+            (None, _) => return Err(Error::NoUnkown),
+            _ => return Err(Error::SeveralUnknown),
+        };
+        let parameters = unknowns
+            .values()
+            .filter_map(|x| Some((x.id(), Dual::variable(x.unknown.borrow().to_f64()?, x.parameter?))))
+            .collect::<BTreeMap<_, _>>();
+        let root = root.to_f64().ok_or(Error::NotRepresentable)?;
+        if parameters.len() + 1 != unknowns.len() {
+            return Err(Error::NotRepresentable);
+        }
+
+        let convert = |a: &S| Dual::constant(a.to_f64().unwrap_or(f64::NAN));
+        let residual = |x: &Dual| {
+            let mut values = parameters.clone();
+            values.insert(solved, x.clone());
+            Some(self.lhs.evaluate_as(&values, &convert)? - self.rhs.evaluate_as(&values, &convert)?)
+        };
+        Ok(Dual::implicit_root(Dual::constant(root), residual))
+    }
+
+    // Solves 'lhs - rhs = 0' if it has a single unknown, rejecting the roots of the denominator
+    pub fn solve(&self) -> Result<Solution<S>, Error> {
+        let element = self.lhs.clone() - self.rhs.clone();
+        element.error()?;
+        let scale = [&self.lhs, &self.rhs, &element]
+            .iter()
+            .fold(0., |max: f64, element| max.max(element.numerator().magnitude()));
+        let element = element.truncated(&self.tolerance, scale);
+
+        // The unknowns cancelled out, the relation holds either always or never
+        let cancelled = element.unknowns().next().is_some() && element.numerator().is_constant();
+        if cancelled && element.numerator().constant_term().is_zero() {
+            log_compute("Error: The relation holds for any value".to_string());
+            return Err(Error::InfiniteSolutions);
+        }
+
+        let id = match Vec::from_iter(element.unknown_ids()).as_slice() {
+            [] if cancelled => {
+                log_compute("Error: The relation never holds".to_string());
+                return Err(Error::NoSolution);
+            },
+            [] => {
+                log_compute("Error: No unknown in the relation".to_string());
+                return Err(Error::NoUnkown);
+            },
+            [id] => *id,
+            _ => {
+                log_compute("Error: Several unknowns in the relation".to_string());
+                return Err(Error::SeveralUnknown);
+            },
+        };
+        let unknown = element
+            .unknowns()
+            .find(|x| x.id() == id)
+            .expect("every unknown of the polynomials is known by the element")
+            .clone();
+
+        let mut complex_roots = vec![];
+        let (roots, method, iterations) = if element.is_algebraic() {
+            let (roots, complex) = polynomial_roots(&element, id, &self.tolerance)?;
+            complex_roots = complex;
+            (roots, Method::Polynomial, 0)
+        } else if let Some((roots, iterations)) = analytic_roots(&element, self.tolerance)? {
+            (roots, Method::Analytic, iterations)
+        } else {
+            let start = unknown.unknown.borrow().to_f64().ok_or(Error::NonLinear)?;
+            let (root, iterations) = numeric_root(&element, id, start)?;
+            (vec![root], Method::Brent, iterations)
+        };
+        if !complex_roots.is_empty() {
+            log_compute(format!("Complex roots: {:?}", complex_roots));
+        }
+
+        // Roots out of the domain of a function or cancelling the denominator are not solutions
+        let mut residual: f64 = 0.;
+        let roots = roots
+            .into_iter()
+            .filter(|root| {
+                let Some(variables) = element.variable_values(&BTreeMap::from([(id, root.clone())])) else {
+                    return false;
+                };
+                if element.denominator().vanishes_at(&variables) {
+                    return false;
+                }
+                let value = element.numerator().evaluate(&variables) / element.denominator().evaluate(&variables);
+                residual = residual.max(value.magnitude());
+                true
+            })
+            .map(|root| S::implicit_root(root, |x| element.evaluate(&BTreeMap::from([(id, x.clone())]))))
+            .collect::<Vec<_>>();
+        log_compute(format!(
+            "Found {} roots with {:?} in {} iterations, residual {:e}",
+            roots.len(),
+            method,
+            iterations,
+            residual
+        ));
+
+        Ok(Solution {
+            unknown,
+            roots: Roots::from(roots),
+            method,
+            iterations,
+            residual,
+            complex_roots,
+        })
+    }
+}
+
+fn polynomial_roots<S: Scalar>(
+    element: &Element<S>,
+    id: UnknownId,
+    tolerance: &Tolerance,
+) -> Result<(Vec<S>, Vec<Complex>), Error> {
+    let coefficients = element
+        .numerator()
+        .univariate_coefficients(id)
+        .expect("the numerator only has one unknown");
+    log_compute(format!("Solving a polynomial of degree {}", coefficients.len() - 1));
+    roots::roots(&coefficients, tolerance)
+}
+
+// Solves 'a * f(argument) + b = 0' as 'argument = f^-1(-b / a)' when 'f' has an inverse
+fn analytic_roots<S: Scalar>(element: &Element<S>, tolerance: Tolerance) -> Result<Option<(Vec<S>, usize)>, Error> {
+    let numerator = element.numerator();
+    let [id] = Vec::from_iter(numerator.unknowns())[..] else {
+        return Ok(None);
+    };
+    let (Some(atom), Some(coefficients)) = (element.atom(id), numerator.univariate_coefficients(id)) else {
+        return Ok(None);
+    };
+    let ([b, a], Some(inverse)) = (coefficients.as_slice(), atom.function.inverse()) else {
+        return Ok(None);
+    };
+
+    let value = -b.clone() / a.clone();
+    if !atom.function.reaches(&value) {
+        log_compute(format!("{:?} never reaches {:?}", atom.function, value));
+        return Ok(Some((vec![], 0)));
+    }
+    let Some(argument) = value.apply(&inverse) else {
+        return Ok(Some((vec![], 0)));
+    };
+    log_compute(format!("Inverting {:?}, its argument must be {:?}", atom.function, argument));
+    let solution = Relation::new(atom.argument.clone(), argument).with_tolerance(tolerance).solve()?;
+    Ok(Some((solution.roots.into_iter().collect(), solution.iterations)))
+}
+
+fn numeric_root<S: Scalar>(element: &Element<S>, id: UnknownId, start: f64) -> Result<(S, usize), Error> {
+    log_compute(format!("Looking for a root numerically from {}", start));
+    let f = |x: f64| {
+        let variables = element.variable_values(&BTreeMap::from([(id, S::from_f64(x)?)]))?;
+        element.numerator().evaluate(&variables).to_f64()
+    };
+    let (root, iterations) = roots::find_root(f, start)?;
+    Ok((S::from_f64(root).ok_or(Error::NonLinear)?, iterations))
+}
+
+impl<S: Scalar> Display for Relation<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.formula().write(Notation::Text))
+    }
+}
+
+impl<S: Scalar> PartialEq for Relation<S> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.lhs == rhs.lhs && self.rhs == rhs.rhs
+    }
+}
+
+impl<S: Scalar> Solution<S> {
+    pub fn unknown(&self) -> &Unknown<S> {
+        &self.unknown
+    }
+
+    pub fn roots(&self) -> &Roots<S> {
+        &self.roots
+    }
+
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    pub fn residual(&self) -> f64 {
+        self.residual
+    }
+
+    // Empty for complex scalars, whose roots are all in 'roots()'
+    pub fn complex_roots(&self) -> &[Complex] {
+        &self.complex_roots
+    }
+}
@@ -0,0 +1,498 @@
+use std::f64::consts::PI;
+
+use crate::complex::Complex;
+use crate::error::Error;
+use crate::scalar::Scalar;
+use crate::tolerance::Tolerance;
+
+fn log_compute(message: String) {
+    logger::log(logger::LogStep::Compute, &message);
+}
+
+// Every real solution of an equation, in ascending order for real scalars
+#[derive(Clone, Debug, PartialEq)]
+pub struct Roots<S = f64>(Vec<S>);
+
+impl<S> Roots<S> {
+    pub fn as_slice(&self) -> &[S] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &S> {
+        self.0.iter()
+    }
+
+    // The root, if there is exactly one
+    pub fn single(&self) -> Option<&S> {
+        match self.0.as_slice() {
+            [root] => Some(root),
+            _ => None,
+        }
+    }
+
+    // The root, or the reason there isn't exactly one
+    pub fn into_single(self) -> Result<S, Error> {
+        let mut roots = self.0.into_iter();
+        match (roots.next(), roots.next()) {
+            (Some(root), None) => Ok(root),
+            (None, _) => Err(Error::NoSolution),
+            _ => Err(Error::SeveralRoots),
+        }
+    }
+}
+
+impl<S> Default for Roots<S> {
+    fn default() -> Self {
+        Roots(vec![])
+    }
+}
+
+impl<S: Scalar> From<S> for Roots<S> {
+    fn from(root: S) -> Self {
+        Roots(vec![root])
+    }
+}
+
+impl<S> From<Vec<S>> for Roots<S> {
+    fn from(roots: Vec<S>) -> Self {
+        Roots(roots)
+    }
+}
+
+impl<S> IntoIterator for Roots<S> {
+    type Item = S;
+    type IntoIter = std::vec::IntoIter<S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+// Finds the roots of 'coefficients[0] + coefficients[1] * x + coefficients[2] * x^2 + ...', along with the complex roots
+// the scalar can't represent
+pub(crate) fn roots<S: Scalar>(coefficients: &[S], tolerance: &Tolerance) -> Result<(Vec<S>, Vec<Complex>), Error> {
+    let scale = coefficients.iter().fold(0., |max: f64, a| max.max(a.magnitude()));
+    let mut coefficients = coefficients.to_vec();
+    while coefficients.last().is_some_and(|a| tolerance.is_negligible(a.magnitude(), scale)) {
+        coefficients.pop();
+    }
+
+    let (roots, complex_roots) = match coefficients.as_slice() {
+        [] | [_] => (vec![], vec![]),
+        [b, a] => (S::linear_roots(b, a)?, vec![]),
+        _ => (S::polynomial_roots(&coefficients)?, S::complex_roots(&coefficients)),
+    };
+    roots.iter().try_for_each(Scalar::check)?;
+    Ok((roots, complex_roots))
+}
+
+const MAX_ITERATIONS: usize = 1000;
+
+fn evaluate(coefficients: &[f64], x: f64) -> (f64, f64) {
+    coefficients
+        .iter()
+        .rev()
+        .fold((0., 0.), |(value, derivative), a| (value * x + a, derivative * x + value))
+}
+
+fn polish(coefficients: &[f64], mut x: f64) -> f64 {
+    for _ in 0..8 {
+        let (value, derivative) = evaluate(coefficients, x);
+        if value == 0. || derivative == 0. {
+            break;
+        }
+        let next = x - value / derivative;
+        if !next.is_finite() || evaluate(coefficients, next).0.abs() >= value.abs() {
+            break;
+        }
+        x = next;
+    }
+    x
+}
+
+// Finds the real roots of 'coefficients[0] + coefficients[1] * x + coefficients[2] * x^2 + ...'
+pub(crate) fn real_roots(coefficients: &[f64]) -> Vec<f64> {
+    let scale = coefficients.iter().fold(0., |max: f64, a| max.max(a.abs()));
+    let mut coefficients = coefficients.to_vec();
+    while coefficients.last().is_some_and(|a| a.abs() <= f64::EPSILON * scale) {
+        coefficients.pop();
+    }
+
+    let mut roots = vec![];
+    let zeros = coefficients.iter().take_while(|a| **a == 0.).count();
+    if zeros > 0 {
+        roots.push(0.);
+        coefficients.drain(..zeros);
+    }
+
+    // Closed forms up to the fourth degree, numeric method above
+    roots.extend(match coefficients.len().saturating_sub(1) {
+        0 => vec![],
+        1 => vec![-coefficients[0] / coefficients[1]],
+        2 => quadratic(coefficients[2], coefficients[1], coefficients[0]),
+        3 => {
+            let a = coefficients[3];
+            cubic(coefficients[2] / a, coefficients[1] / a, coefficients[0] / a)
+        },
+        4 => {
+            let a = coefficients[4];
+            quartic(coefficients[3] / a, coefficients[2] / a, coefficients[1] / a, coefficients[0] / a)
+        },
+        _ => {
+            let coefficients: Vec<Complex> = coefficients.iter().cloned().map(Complex::from).collect();
+            durand_kerner(&coefficients).into_iter().filter(is_real).map(|root| root.re()).collect()
+        },
+    }.into_iter().map(|root| polish(&coefficients, root)));
+
+    roots.sort_by(f64::total_cmp);
+    roots.dedup_by(|a, b| (*a - *b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.));
+    roots
+}
+
+// a * x^2 + b * x + c = 0
+fn quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let discriminant = b * b - 4. * a * c;
+    let tolerance = 4. * f64::EPSILON * (b * b).max((4. * a * c).abs());
+    if discriminant.abs() <= tolerance {
+        vec![-b / (2. * a)]
+    } else if discriminant < 0. {
+        vec![]
+    } else {
+        // Avoids the cancellation of '-b + sqrt(discriminant)'
+        let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+        if q == 0. {
+            vec![-discriminant.sqrt() / (2. * a), discriminant.sqrt() / (2. * a)]
+        } else {
+            vec![q / a, c / q]
+        }
+    }
+}
+
+// x^3 + a * x^2 + b * x + c = 0
+fn cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    // x = t - a / 3 gives t^3 + p * t + q = 0
+    let p = b - a * a / 3.;
+    let q = 2. * a * a * a / 27. - a * b / 3. + c;
+    let shift = -a / 3.;
+
+    let discriminant = (q / 2.).powi(2) + (p / 3.).powi(3);
+    let tolerance = 16. * f64::EPSILON * (q / 2.).powi(2).max((p / 3.).powi(3).abs());
+    if discriminant.abs() <= tolerance {
+        if p == 0. {
+            vec![shift]
+        } else {
+            vec![3. * q / p + shift, -3. * q / (2. * p) + shift]
+        }
+    } else if discriminant > 0. {
+        let sqrt = discriminant.sqrt();
+        vec![(-q / 2. + sqrt).cbrt() + (-q / 2. - sqrt).cbrt() + shift]
+    } else {
+        let r = 2. * (-p / 3.).sqrt();
+        let phi = ((3. * q / (2. * p)) * (-3. / p).sqrt()).clamp(-1., 1.).acos() / 3.;
+        (0..3)
+            .map(|k| r * (phi - 2. * PI * k as f64 / 3.).cos() + shift)
+            .collect()
+    }
+}
+
+// x^4 + a * x^3 + b * x^2 + c * x + d = 0
+fn quartic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    // x = y - a / 4 gives y^4 + p * y^2 + q * y + r = 0
+    let p = b - 3. * a * a / 8.;
+    let q = c - a * b / 2. + a * a * a / 8.;
+    let r = d - a * c / 4. + a * a * b / 16. - 3. * a.powi(4) / 256.;
+    let shift = -a / 4.;
+
+    let scale = p.abs().max(r.abs().sqrt()).max(1.);
+    let ys = if q.abs() <= 16. * f64::EPSILON * scale.powf(1.5) {
+        // Biquadratic equation in y^2
+        quadratic(1., p, r)
+            .into_iter()
+            .filter(|z| *z >= 0.)
+            .flat_map(|z| [-z.sqrt(), z.sqrt()])
+            .collect()
+    } else {
+        // Ferrari's method: the resolvent cubic always has a positive root
+        let m = cubic(p, (p * p - 4. * r) / 4., -q * q / 8.)
+            .into_iter()
+            .fold(0., f64::max);
+        let s = (2. * m).sqrt();
+        let mut ys = quadratic(1., -s, p / 2. + m + q / (2. * s));
+        ys.extend(quadratic(1., s, p / 2. + m - q / (2. * s)));
+        ys
+    };
+
+    ys.into_iter().map(|y| y + shift).collect()
+}
+
+// Finds all the complex roots at once from points spread on a circle bounding them
+fn durand_kerner(coefficients: &[Complex]) -> Vec<Complex> {
+    let leading = coefficients[coefficients.len() - 1];
+    let monic: Vec<Complex> = coefficients.iter().map(|a| *a / leading).collect();
+    let degree = monic.len() - 1;
+
+    // Cauchy's bound on the norm of the roots
+    let bound = 1. + monic[..degree].iter().fold(0., |max: f64, a| max.max(a.norm()));
+    let seed = Complex::new(0.4, 0.9);
+    let mut roots: Vec<Complex> = (0..degree)
+        .scan(Complex::from(bound / seed.norm()), |z, _| {
+            *z = *z * seed;
+            Some(*z)
+        })
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut change: f64 = 0.;
+        for i in 0..degree {
+            let denominator = roots
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(Complex::one(), |product, (_, root)| product * (roots[i] - *root));
+            let step = evaluate_complex(&monic, roots[i]).0 / denominator;
+            if step.check().is_ok() {
+                roots[i] = roots[i] - step;
+                change = change.max(step.norm());
+            }
+        }
+        if change <= f64::EPSILON * bound {
+            break;
+        }
+    }
+    roots
+}
+
+// Whether a root found as complex is real, up to the rounding errors of the numeric method
+fn is_real(root: &Complex) -> bool {
+    root.im().abs() <= 1e-7 * root.norm().max(1.)
+}
+
+fn evaluate_complex(coefficients: &[Complex], z: Complex) -> (Complex, Complex) {
+    coefficients
+        .iter()
+        .rev()
+        .fold((Complex::zero(), Complex::zero()), |(value, derivative), a| {
+            (value * z + *a, derivative * z + value)
+        })
+}
+
+fn polish_complex(coefficients: &[Complex], mut z: Complex) -> Complex {
+    for _ in 0..8 {
+        let (value, derivative) = evaluate_complex(coefficients, z);
+        if value.is_zero() || derivative.is_zero() {
+            break;
+        }
+        let next = z - value / derivative;
+        if next.check().is_err() || evaluate_complex(coefficients, next).0.norm() >= value.norm() {
+            break;
+        }
+        z = next;
+    }
+    z
+}
+
+// Finds every complex root of 'coefficients[0] + coefficients[1] * x + coefficients[2] * x^2 + ...', sorted by real
+// then imaginary part
+pub(crate) fn complex_roots(coefficients: &[Complex]) -> Vec<Complex> {
+    let scale = coefficients.iter().fold(0., |max: f64, a| max.max(a.norm()));
+    let mut coefficients = coefficients.to_vec();
+    while coefficients.last().is_some_and(|a| a.norm() <= f64::EPSILON * scale) {
+        coefficients.pop();
+    }
+
+    let mut roots = vec![];
+    let zeros = coefficients.iter().take_while(|a| a.is_zero()).count();
+    if zeros > 0 {
+        roots.push(Complex::zero());
+        coefficients.drain(..zeros);
+    }
+
+    roots.extend(match coefficients.len().saturating_sub(1) {
+        0 => vec![],
+        1 => vec![-coefficients[0] / coefficients[1]],
+        2 => {
+            // Avoids the cancellation of '-b + sqrt(discriminant)' by choosing the sign of the square root
+            let (a, b, c) = (coefficients[2], coefficients[1], coefficients[0]);
+            let sqrt = (b * b - Complex::from(4.) * a * c).sqrt();
+            let sqrt = if (b.conj() * sqrt).re() < 0. { -sqrt } else { sqrt };
+            let q = Complex::from(-0.5) * (b + sqrt);
+            if q.is_zero() {
+                vec![Complex::zero()]
+            } else {
+                vec![q / a, c / q]
+            }
+        },
+        _ => durand_kerner(&coefficients),
+    }.into_iter().map(|root| polish_complex(&coefficients, root)));
+
+    // Roots real up to rounding errors are made real
+    let mut roots: Vec<Complex> = roots
+        .into_iter()
+        .map(|root| if is_real(&root) { Complex::from(root.re()) } else { root })
+        .collect();
+    roots.sort_by(|a, b| a.re().total_cmp(&b.re()).then(a.im().total_cmp(&b.im())));
+    roots.dedup_by(|a, b| (*a - *b).norm() <= 1e-9 * a.norm().max(b.norm()).max(1.));
+    roots
+}
+
+const MAX_BRACKET_STEPS: usize = 200;
+
+// Finds a root of 'f' near 'start', returned with the number of iterations needed
+// A sign change is looked for further and further on both sides, then the root is isolated with Brent's method
+pub(crate) fn find_root<F>(f: F, start: f64) -> Result<(f64, usize), Error>
+where
+    F: Fn(f64) -> Option<f64>,
+{
+    let f = |x: f64| f(x).filter(|y| y.is_finite());
+    if f(start) == Some(0.) {
+        return Ok((start, 0));
+    }
+
+    let mut step = start.abs().max(1.) * 1e-2;
+    let mut sides = [(start, f(start)), (start, f(start))];
+    for _ in 0..MAX_BRACKET_STEPS {
+        for (side, direction) in sides.iter_mut().zip([-1., 1.]) {
+            let x = side.0 + direction * step;
+            let y = f(x);
+            match (side.1, y) {
+                (_, Some(0.)) => return Ok((x, 0)),
+                (Some(previous), Some(y)) if previous.signum() != y.signum() => {
+                    return brent(&f, side.0, x, previous, y);
+                },
+                _ => *side = (x, y),
+            }
+        }
+        step *= 1.5;
+    }
+
+    log_compute("Error: No sign change found around the initial value".to_string());
+    Err(Error::NoConvergence)
+}
+
+// Brent's method, 'f(a)' and 'f(b)' must have opposite signs
+fn brent<F>(f: &F, mut a: f64, mut b: f64, mut fa: f64, mut fb: f64) -> Result<(f64, usize), Error>
+where
+    F: Fn(f64) -> Option<f64>,
+{
+    let (mut c, mut fc) = (b, fb);
+    let (mut d, mut e) = (b - a, b - a);
+    for iteration in 1..=MAX_ITERATIONS {
+        if fb.signum() == fc.signum() {
+            (c, fc) = (a, fa);
+            (d, e) = (b - a, b - a);
+        }
+        if fc.abs() < fb.abs() {
+            (a, fa) = (b, fb);
+            (b, fb) = (c, fc);
+            (c, fc) = (a, fa);
+        }
+
+        let tolerance = 2. * f64::EPSILON * b.abs() + f64::MIN_POSITIVE;
+        let middle = (c - b) / 2.;
+        if middle.abs() <= tolerance || fb == 0. {
+            return Ok((b, iteration));
+        }
+
+        // Inverse quadratic interpolation or secant, if it stays well inside the bracket, else bisection
+        if e.abs() >= tolerance && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2. * middle * s, 1. - s)
+            } else {
+                let (q, r) = (fa / fc, fb / fc);
+                (
+                    s * (2. * middle * q * (q - r) - (b - a) * (r - 1.)),
+                    (q - 1.) * (r - 1.) * (s - 1.),
+                )
+            };
+            if p > 0. {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2. * p < (3. * middle * q - (tolerance * q).abs()).min((e * q).abs()) {
+                (e, d) = (d, p / q);
+            } else {
+                (d, e) = (middle, middle);
+            }
+        } else {
+            (d, e) = (middle, middle);
+        }
+
+        (a, fa) = (b, fb);
+        b += if d.abs() > tolerance { d } else { tolerance.copysign(middle) };
+        fb = f(b).ok_or(Error::NoConvergence)?;
+    }
+
+    log_compute("Error: Brent's method did not converge".to_string());
+    Err(Error::NoConvergence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(coefficients: &[f64], expected: &[f64]) {
+        let roots = real_roots(coefficients);
+        assert_eq!(roots.len(), expected.len(), "{:?} != {:?}", roots, expected);
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!((root - expected).abs() < 1e-9, "{:?} != {:?}", roots, expected);
+        }
+    }
+
+    #[test]
+    fn test_closed_form_roots() {
+        // 2x - 4 = 0
+        check(&[-4., 2.], &[2.]);
+
+        // x^2 - 4 = 0
+        check(&[-4., 0., 1.], &[-2., 2.]);
+
+        // x^2 - 2x + 1 = 0
+        check(&[1., -2., 1.], &[1.]);
+
+        // x^2 + 1 = 0
+        check(&[1., 0., 1.], &[]);
+
+        // x^2 - 3x = 0
+        check(&[0., -3., 1.], &[0., 3.]);
+
+        // (x - 1)(x - 2)(x - 3) = 0
+        check(&[-6., 11., -6., 1.], &[1., 2., 3.]);
+
+        // (x - 1)^2 (x + 2) = 0
+        check(&[2., -3., 0., 1.], &[-2., 1.]);
+
+        // x^3 + x + 1 = 0
+        check(&[1., 1., 0., 1.], &[-0.6823278038280193]);
+
+        // (x - 1)(x - 2)(x + 3)(x + 4) = 0
+        check(&[24., -22., -7., 4., 1.], &[-4., -3., 1., 2.]);
+
+        // x^4 - 5x^2 + 4 = 0
+        check(&[4., 0., -5., 0., 1.], &[-2., -1., 1., 2.]);
+
+        // x^4 + 1 = 0
+        check(&[1., 0., 0., 0., 1.], &[]);
+    }
+
+    #[test]
+    fn test_numeric_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)(x - 5) = 0
+        check(&[-120., 274., -225., 85., -15., 1.], &[1., 2., 3., 4., 5.]);
+
+        // x^6 - 1 = 0
+        check(&[-1., 0., 0., 0., 0., 0., 1.], &[-1., 1.]);
+
+        // x^5 + 1 = 0
+        check(&[1., 0., 0., 0., 0., 1.], &[-1.]);
+    }
+}
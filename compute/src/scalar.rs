@@ -0,0 +1,297 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::complex::Complex;
+use crate::error::Error;
+use crate::function::Function;
+use crate::roots;
+use crate::tolerance::Tolerance;
+
+// The numbers elements are made of, f64 by default
+// 'static, so that the copies of the fields lowered while a relation is written can be stored
+pub trait Scalar:
+    'static
+    + Clone
+    + Debug
+    + Display
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    // Size of the scalar, used to choose pivots and scale tolerances
+    fn magnitude(&self) -> f64;
+
+    // Equality up to the rounding errors on values as big as 'scale', exact by default
+    fn approx_eq(&self, other: &Self, _scale: f64) -> bool {
+        self == other
+    }
+
+    // The tolerance used to cancel coefficients when solving, exact by default
+    fn default_tolerance() -> Tolerance {
+        Tolerance::EXACT
+    }
+
+    // Fails if the scalar results from an invalid operation, like an overflow
+    fn check(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    // Whether the scalar is written with a minus sign
+    fn is_negative(&self) -> bool {
+        self.to_f64().is_some_and(|value| value < 0.)
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        None
+    }
+
+    fn from_f64(_value: f64) -> Option<Self> {
+        None
+    }
+
+    // The root of 'f' with its derivatives, for scalars carrying derivatives that a numeric solver can't follow
+    fn implicit_root<F>(root: Self, _f: F) -> Self
+    where
+        F: Fn(&Self) -> Option<Self>,
+    {
+        root
+    }
+
+    // Value of a function like 'exp', computed as f64 by default, None out of its domain
+    fn apply(&self, function: &Function<Self>) -> Option<Self> {
+        Self::from_f64(function.evaluate_f64(self.to_f64()?)?)
+    }
+
+    // Roots of 'a * x + b', with a non null 'a'
+    fn linear_roots(b: &Self, a: &Self) -> Result<Vec<Self>, Error> {
+        Ok(vec![-b.clone() / a.clone()])
+    }
+
+    // Roots of 'coefficients[0] + coefficients[1] * x + ...' of degree 2 or more, found as f64 by default
+    fn polynomial_roots(coefficients: &[Self]) -> Result<Vec<Self>, Error> {
+        let coefficients = coefficients
+            .iter()
+            .map(Scalar::to_f64)
+            .collect::<Option<Vec<f64>>>()
+            .ok_or(Error::NonLinear)?;
+        roots::real_roots(&coefficients)
+            .into_iter()
+            .map(|root| Self::from_f64(root).ok_or(Error::NonLinear))
+            .collect()
+    }
+
+    // The roots of a polynomial of degree 2 or more that are not real, found as complex numbers by default
+    fn complex_roots(coefficients: &[Self]) -> Vec<Complex> {
+        let Some(coefficients) = coefficients
+            .iter()
+            .map(|a| a.to_f64().map(Complex::from))
+            .collect::<Option<Vec<Complex>>>()
+        else {
+            return vec![];
+        };
+        roots::complex_roots(&coefficients)
+            .into_iter()
+            .filter(|root| !root.is_real())
+            .collect()
+    }
+}
+
+macro_rules! impl_float_scalar {
+    ($float:ty, $tolerance:expr) => {
+        impl Scalar for $float {
+            fn zero() -> Self {
+                0.
+            }
+
+            fn one() -> Self {
+                1.
+            }
+
+            fn magnitude(&self) -> f64 {
+                self.abs() as f64
+            }
+
+            fn approx_eq(&self, other: &Self, scale: f64) -> bool {
+                ((self - other).abs() as f64) <= $tolerance * scale.max(self.magnitude()).max(other.magnitude())
+            }
+
+            fn default_tolerance() -> Tolerance {
+                Tolerance::relative($tolerance)
+            }
+
+            fn to_f64(&self) -> Option<f64> {
+                Some(*self as f64)
+            }
+
+            fn from_f64(value: f64) -> Option<Self> {
+                Some(value as $float)
+            }
+        }
+    };
+}
+
+impl_float_scalar!(f64, 1e-12);
+impl_float_scalar!(f32, 1e-5);
+
+// Operators with a scalar on the left can't be implemented generically, so each scalar type gets its own
+macro_rules! impl_scalar_lhs_ops {
+    ($scalar:ty) => {
+        impl std::ops::Add<$crate::element::Element<$scalar>> for $scalar {
+            type Output = $crate::element::Element<$scalar>;
+
+            fn add(self, rhs: $crate::element::Element<$scalar>) -> Self::Output {
+                rhs + self
+            }
+        }
+
+        impl std::ops::Sub<$crate::element::Element<$scalar>> for $scalar {
+            type Output = $crate::element::Element<$scalar>;
+
+            fn sub(self, rhs: $crate::element::Element<$scalar>) -> Self::Output {
+                -rhs + self
+            }
+        }
+
+        impl std::ops::Mul<$crate::element::Element<$scalar>> for $scalar {
+            type Output = $crate::element::Element<$scalar>;
+
+            fn mul(self, rhs: $crate::element::Element<$scalar>) -> Self::Output {
+                rhs * self
+            }
+        }
+
+        impl std::ops::Div<$crate::element::Element<$scalar>> for $scalar {
+            type Output = $crate::element::Element<$scalar>;
+
+            fn div(self, rhs: $crate::element::Element<$scalar>) -> Self::Output {
+                $crate::element::Element::known_over(self, rhs)
+            }
+        }
+
+        impl PartialEq<$crate::element::Element<$scalar>> for $scalar {
+            fn eq(&self, rhs: &$crate::element::Element<$scalar>) -> bool {
+                rhs == self
+            }
+        }
+
+        impl std::ops::Add<$crate::equation::EquationElement<$scalar>> for $scalar {
+            type Output = $crate::element::Element<$scalar>;
+
+            fn add(self, rhs: $crate::equation::EquationElement<$scalar>) -> Self::Output {
+                $crate::element::Element::from(rhs) + self
+            }
+        }
+
+        impl std::ops::Sub<$crate::equation::EquationElement<$scalar>> for $scalar {
+            type Output = $crate::element::Element<$scalar>;
+
+            fn sub(self, rhs: $crate::equation::EquationElement<$scalar>) -> Self::Output {
+                -$crate::element::Element::from(rhs) + self
+            }
+        }
+
+        impl std::ops::Mul<$crate::equation::EquationElement<$scalar>> for $scalar {
+            type Output = $crate::element::Element<$scalar>;
+
+            fn mul(self, rhs: $crate::equation::EquationElement<$scalar>) -> Self::Output {
+                $crate::element::Element::from(rhs) * self
+            }
+        }
+
+        impl std::ops::Div<$crate::equation::EquationElement<$scalar>> for $scalar {
+            type Output = $crate::element::Element<$scalar>;
+
+            fn div(self, rhs: $crate::equation::EquationElement<$scalar>) -> Self::Output {
+                self / $crate::element::Element::from(rhs)
+            }
+        }
+
+        impl PartialEq<$crate::equation::EquationElement<$scalar>> for $scalar {
+            fn eq(&self, rhs: &$crate::equation::EquationElement<$scalar>) -> bool {
+                rhs == self
+            }
+        }
+
+        impl std::ops::Add<$crate::expression::Expression<$scalar>> for $scalar {
+            type Output = $crate::expression::Expression<$scalar>;
+
+            fn add(self, rhs: $crate::expression::Expression<$scalar>) -> Self::Output {
+                $crate::expression::Expression::Constant(self) + rhs
+            }
+        }
+
+        impl std::ops::Sub<$crate::expression::Expression<$scalar>> for $scalar {
+            type Output = $crate::expression::Expression<$scalar>;
+
+            fn sub(self, rhs: $crate::expression::Expression<$scalar>) -> Self::Output {
+                $crate::expression::Expression::Constant(self) - rhs
+            }
+        }
+
+        impl std::ops::Mul<$crate::expression::Expression<$scalar>> for $scalar {
+            type Output = $crate::expression::Expression<$scalar>;
+
+            fn mul(self, rhs: $crate::expression::Expression<$scalar>) -> Self::Output {
+                $crate::expression::Expression::Constant(self) * rhs
+            }
+        }
+
+        impl std::ops::Div<$crate::expression::Expression<$scalar>> for $scalar {
+            type Output = $crate::expression::Expression<$scalar>;
+
+            fn div(self, rhs: $crate::expression::Expression<$scalar>) -> Self::Output {
+                $crate::expression::Expression::Constant(self) / rhs
+            }
+        }
+    };
+}
+pub(crate) use impl_scalar_lhs_ops;
+
+impl_scalar_lhs_ops!(f64);
+impl_scalar_lhs_ops!(f32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::element::Element;
+    use crate::roots::Roots;
+
+    #[test]
+    fn test_float_scalars() {
+        assert!(1f64.approx_eq(&(1. + 1e-14), 1.));
+        assert!(!1f64.approx_eq(&(1. + 1e-6), 1.));
+        assert!(1f32.approx_eq(&(1. + 1e-6), 1.));
+        assert!(0f64.approx_eq(&1e-10, 1e3));
+        assert_eq!(2f32.magnitude(), 2.);
+        assert_eq!((-2f64).magnitude(), 2.);
+    }
+
+    #[test]
+    fn test_single_precision_element() {
+        let x = Rc::new(RefCell::new(0f32));
+
+        // 2 / x + 1 = 5
+        let relation = (2f32 / Element::new_unknown(x.clone()) + 1.).equals(5.);
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(0.5f32));
+
+        // x^2 - 3x + 2 = 0
+        let x = Element::new_unknown(x);
+        let relation = (x.clone() * x.clone() - 3f32 * x + 2.).equals(0.);
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(vec![1f32, 2.]));
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::BTreeMap;
+
+use crate::element::Element;
+use crate::error::Error;
+use crate::relation::Relation;
+use crate::scalar::Scalar;
+use crate::unknown::{Unknown, UnknownId};
+
+type Unknowns<S> = BTreeMap<UnknownId, Unknown<S>>;
+
+fn log_compute(message: String) {
+    logger::log(logger::LogStep::Compute, &message);
+}
+
+// Several linear equations sharing their unknowns, solved all at once
+#[derive(Clone, Debug)]
+pub struct LinearSystem<S = f64> {
+    equations: Vec<Element<S>>,
+}
+
+impl<S> Default for LinearSystem<S> {
+    fn default() -> Self {
+        Self { equations: vec![] }
+    }
+}
+
+impl<S: Scalar> LinearSystem<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds the equation 'lhs = rhs' to the system
+    pub fn add_equation<L, R>(&mut self, lhs: L, rhs: R)
+    where
+        L: Into<Element<S>>,
+        R: Into<Element<S>>,
+    {
+        self.equations.push(lhs.into() - rhs.into());
+    }
+
+    pub fn add_relation(&mut self, relation: Relation<S>) {
+        self.add_equation(relation.lhs().clone(), relation.rhs().clone());
+    }
+
+    // Solves the system and writes the value of every unknown
+    pub fn solve(&self) -> Result<(), Error> {
+        let (unknowns, mut rows) = self.augmented_matrix()?;
+        log_compute(format!(
+            "Solving {} equations with {} unknowns",
+            rows.len(),
+            unknowns.len()
+        ));
+
+        let values: BTreeMap<UnknownId, S> = unknowns
+            .keys()
+            .cloned()
+            .zip(gauss_jordan(&mut rows, unknowns.len())?)
+            .collect();
+        self.write(&unknowns, values)
+    }
+
+    // Writes the values minimizing the sum of the squared residuals, for redundant equations that do not all hold
+    // The residual of each equation tells which one disagrees with the others
+    pub fn solve_least_squares(&self) -> Result<LeastSquares, Error> {
+        let (unknowns, rows) = self.augmented_matrix()?;
+        let columns = unknowns.len();
+        if rows.len() < columns {
+            log_compute("Error: Fewer equations than unknowns".to_string());
+            return Err(Error::SingularSystem);
+        }
+        let matrix = rows
+            .iter()
+            .map(|row| row.iter().map(Scalar::to_f64).collect::<Option<Vec<f64>>>())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::NotRepresentable)?;
+        log_compute(format!(
+            "Solving {} equations with {} unknowns by least squares",
+            matrix.len(),
+            columns
+        ));
+
+        let solution = householder_least_squares(matrix.clone(), columns)?;
+        let residuals: Vec<f64> = matrix
+            .iter()
+            .map(|row| row.iter().zip(&solution).map(|(a, x)| a * x).sum::<f64>() - row[columns])
+            .collect();
+        let values = unknowns
+            .keys()
+            .zip(&solution)
+            .map(|(id, value)| S::from_f64(*value).map(|value| (*id, value)))
+            .collect::<Option<BTreeMap<UnknownId, S>>>()
+            .ok_or(Error::NotRepresentable)?;
+        self.write(&unknowns, values)?;
+
+        let least_squares = LeastSquares { residuals };
+        log_compute(format!("Residual norm {:e}", least_squares.residual_norm()));
+        Ok(least_squares)
+    }
+
+    // The unknowns of the equations, and the rows 'a1, a2, ..., -b' of the equations 'a1 * x1 + a2 * x2 + ... + b = 0'
+    fn augmented_matrix(&self) -> Result<(Unknowns<S>, Vec<Vec<S>>), Error> {
+        let mut unknowns: Unknowns<S> = BTreeMap::new();
+        let mut coefficients = vec![];
+        for equation in &self.equations {
+            equation.error()?;
+            // 'numerator / denominator = 0' is solved as 'numerator = 0'
+            let polynomial = equation.numerator();
+            match polynomial.linear_coefficients().filter(|_| equation.is_algebraic()) {
+                Some(ax) => coefficients.push((ax, polynomial.constant_term())),
+                None => {
+                    log_compute("Error: Non linear equation in the system".to_string());
+                    return Err(Error::NonLinear);
+                },
+            }
+            // Unknowns cancelled out of the equation are left aside
+            for x in equation.unknowns().filter(|x| polynomial.unknowns().contains(&x.id())) {
+                unknowns.entry(x.id()).or_insert_with(|| x.clone());
+            }
+        }
+        if unknowns.is_empty() {
+            log_compute("Error: No unknown in the system".to_string());
+            return Err(Error::NoUnkown);
+        }
+
+        let columns: BTreeMap<UnknownId, usize> = unknowns
+            .keys()
+            .enumerate()
+            .map(|(column, id)| (*id, column))
+            .collect();
+        let rows = coefficients
+            .into_iter()
+            .map(|(ax, b)| {
+                let mut row = vec![S::zero(); columns.len() + 1];
+                for (x, a) in ax {
+                    row[columns[&x]] = a;
+                }
+                row[columns.len()] = -b;
+                row
+            })
+            .collect();
+        Ok((unknowns, rows))
+    }
+
+    // Writes the values of the unknowns, unless they cancel a denominator
+    fn write(&self, unknowns: &Unknowns<S>, values: BTreeMap<UnknownId, S>) -> Result<(), Error> {
+        for equation in &self.equations {
+            let denominator = equation.denominator();
+            if !denominator.unknowns().iter().all(|x| values.contains_key(x)) {
+                log_compute("Error: Unknown only found in a denominator".to_string());
+                return Err(Error::SingularSystem);
+            }
+            if denominator.vanishes_at(&values) {
+                log_compute("Error: Solution cancelling a denominator".to_string());
+                return Err(Error::DivisionByZero);
+            }
+        }
+        for (x, value) in unknowns.values().zip(values.into_values()) {
+            *x.unknown.borrow_mut() = value;
+        }
+
+        Ok(())
+    }
+}
+
+// The residuals left by a least squares solution
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeastSquares {
+    // 'lhs - rhs' of each equation, in the order they were added, scaled like the numerators of the equations
+    residuals: Vec<f64>,
+}
+
+impl LeastSquares {
+    pub fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+
+    pub fn residual_norm(&self) -> f64 {
+        self.residuals.iter().map(|residual| residual * residual).sum::<f64>().sqrt()
+    }
+
+    // The index of the equation with the largest residual, the most likely to be wrong
+    pub fn worst_equation(&self) -> Option<usize> {
+        (0..self.residuals.len()).max_by(|&i, &j| self.residuals[i].abs().total_cmp(&self.residuals[j].abs()))
+    }
+}
+
+// Minimizes '|A * x - b|' for the augmented matrix 'A | b' with Householder reflections, so 'A = Q * R'
+// The normal equations are avoided as they square the condition number
+fn householder_least_squares(mut rows: Vec<Vec<f64>>, columns: usize) -> Result<Vec<f64>, Error> {
+    let scale = rows
+        .iter()
+        .flat_map(|row| &row[..columns])
+        .fold(0., |max: f64, value| max.max(value.abs()));
+    let negligible = scale * f64::EPSILON * (rows.len() as f64);
+
+    for k in 0..columns {
+        let length = rows[k..].iter().map(|row| row[k] * row[k]).sum::<f64>().sqrt();
+        if length <= negligible {
+            log_compute(format!("Error: Singular system, column {} depends on the previous ones", k));
+            return Err(Error::SingularSystem);
+        }
+        // The reflection sending the column to '(alpha, 0, ..., 0)', with the sign avoiding a cancellation
+        let alpha = -rows[k][k].signum() * length;
+        let mut v: Vec<f64> = rows[k..].iter().map(|row| row[k]).collect();
+        v[0] -= alpha;
+        let square: f64 = v.iter().map(|v| v * v).sum();
+        for j in k..=columns {
+            let dot: f64 = v.iter().zip(&rows[k..]).map(|(v, row)| v * row[j]).sum();
+            let factor = 2. * dot / square;
+            rows[k..].iter_mut().zip(&v).for_each(|(row, v)| row[j] -= factor * v);
+        }
+    }
+
+    // Back substitution of 'R * x = Q^T * b'
+    let mut values = vec![0.; columns];
+    for k in (0..columns).rev() {
+        let sum: f64 = (k + 1..columns).map(|j| rows[k][j] * values[j]).sum();
+        values[k] = (rows[k][columns] - sum) / rows[k][k];
+    }
+    Ok(values)
+}
+
+// Reduces the augmented matrix 'rows' with partial pivoting, and returns the value of each column
+pub(crate) fn gauss_jordan<S: Scalar>(rows: &mut [Vec<S>], columns: usize) -> Result<Vec<S>, Error> {
+    let scale = rows
+        .iter()
+        .flatten()
+        .fold(0., |max: f64, value| max.max(value.magnitude()));
+    let tolerance = scale * (rows.len().max(columns) as f64);
+    let negligible = |value: &S| value.approx_eq(&S::zero(), tolerance);
+
+    let mut pivots = vec![];
+    for column in 0..columns {
+        let rank = pivots.len();
+        let pivot = (rank..rows.len())
+            .max_by(|&i, &j| rows[i][column].magnitude().total_cmp(&rows[j][column].magnitude()));
+        let pivot = match pivot {
+            Some(pivot) if !negligible(&rows[pivot][column]) => pivot,
+            _ => continue,
+        };
+        rows.swap(rank, pivot);
+
+        let factor = rows[rank][column].clone();
+        rows[rank].iter_mut().for_each(|value| *value = value.clone() / factor.clone());
+        let pivot_row = rows[rank].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != rank {
+                let factor = row[column].clone();
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .skip(column)
+                    .for_each(|(value, pivot)| *value = value.clone() - factor.clone() * pivot.clone());
+            }
+        }
+        pivots.push(column);
+    }
+
+    if rows[pivots.len()..].iter().any(|row| !negligible(&row[columns])) {
+        log_compute("Error: Inconsistent system".to_string());
+        return Err(Error::InconsistentSystem);
+    }
+    if pivots.len() < columns {
+        log_compute(format!("Error: Singular system, rank {} < {}", pivots.len(), columns));
+        return Err(Error::SingularSystem);
+    }
+
+    let values: Vec<S> = rows.iter().take(columns).map(|row| row[columns].clone()).collect();
+    values.iter().try_for_each(Scalar::check)?;
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Setup {
+        x: Rc<RefCell<f64>>,
+        y: Rc<RefCell<f64>>,
+        z: Rc<RefCell<f64>>,
+    }
+
+    impl Setup {
+        fn new() -> Self {
+            Self {
+                x: Rc::new(RefCell::new(0.)),
+                y: Rc::new(RefCell::new(0.)),
+                z: Rc::new(RefCell::new(0.)),
+            }
+        }
+
+        fn x(&self) -> Element {
+            Element::new_unknown(self.x.clone())
+        }
+
+        fn y(&self) -> Element {
+            Element::new_unknown(self.y.clone())
+        }
+
+        fn z(&self) -> Element {
+            Element::new_unknown(self.z.clone())
+        }
+
+        fn check(rc: &Rc<RefCell<f64>>, value: f64) {
+            assert!((*rc.borrow() - value).abs() < 1e-12, "{} != {}", rc.borrow(), value);
+        }
+    }
+
+    #[test]
+    fn test_solve_system() {
+        let setup = Setup::new();
+
+        // x + y = 3, x - y = 1
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.x() + setup.y(), 3.);
+        system.add_equation(setup.x() - setup.y(), 1.);
+        assert_eq!(system.solve(), Ok(()));
+        Setup::check(&setup.x, 2.);
+        Setup::check(&setup.y, 1.);
+
+        // y + z = 5, x + z = 4, x + y = 3 (needs pivoting)
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.y() + setup.z(), 5.);
+        system.add_equation(setup.x() + setup.z(), 4.);
+        system.add_equation(setup.x() + setup.y(), 3.);
+        assert_eq!(system.solve(), Ok(()));
+        Setup::check(&setup.x, 1.);
+        Setup::check(&setup.y, 2.);
+        Setup::check(&setup.z, 3.);
+
+        // 2x = y + 1, x = 3, 3x - y = 4 (more equations than unknowns)
+        let mut system = LinearSystem::new();
+        system.add_equation(2. * setup.x(), setup.y() + 1.);
+        system.add_equation(setup.x(), 3.);
+        system.add_equation(3. * setup.x() - setup.y(), 4.);
+        assert_eq!(system.solve(), Ok(()));
+        Setup::check(&setup.x, 3.);
+        Setup::check(&setup.y, 5.);
+
+        // 6 / x = y, y = 2
+        let mut system = LinearSystem::new();
+        system.add_equation(6. / setup.x(), setup.y());
+        system.add_equation(setup.y(), 2.);
+        assert_eq!(system.solve(), Err(Error::NonLinear));
+
+        // (x + y) / 2 = 4, x / (y + 1) = 1
+        let mut system = LinearSystem::new();
+        system.add_equation((setup.x() + setup.y()) / 2., 4.);
+        system.add_equation(setup.x() / (setup.y() + 1.), 1.);
+        assert_eq!(system.solve(), Ok(()));
+        Setup::check(&setup.x, 4.5);
+        Setup::check(&setup.y, 3.5);
+    }
+
+    #[test]
+    fn test_solve_system_errors() {
+        let setup = Setup::new();
+
+        // x + y = 3, 2x + 2y = 6
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.x() + setup.y(), 3.);
+        system.add_equation(2. * setup.x() + 2. * setup.y(), 6.);
+        assert_eq!(system.solve(), Err(Error::SingularSystem));
+
+        // x + y = 3, x + y = 4
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.x() + setup.y(), 3.);
+        system.add_equation(setup.x() + setup.y(), 4.);
+        assert_eq!(system.solve(), Err(Error::InconsistentSystem));
+
+        // 1 = 1
+        let mut system = LinearSystem::new();
+        system.add_equation(1., 1.);
+        assert_eq!(system.solve(), Err(Error::NoUnkown));
+
+        // x * y = 1
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.x() * setup.y(), 1.);
+        assert_eq!(system.solve(), Err(Error::NonLinear));
+
+        // x / (y - 1) = 0, y = 1
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.x() / (setup.y() - 1.), 0.);
+        system.add_equation(setup.y(), 1.);
+        assert_eq!(system.solve(), Err(Error::DivisionByZero));
+    }
+
+    #[test]
+    fn test_least_squares() {
+        let setup = Setup::new();
+
+        // Three measurements of x, the last one being off
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.x(), 2.);
+        system.add_equation(setup.x() * 2., 4.);
+        system.add_equation(setup.x(), 5.);
+        assert_eq!(system.solve(), Err(Error::InconsistentSystem));
+        let least_squares = system.solve_least_squares().unwrap();
+        Setup::check(&setup.x, 2.5);
+        assert_eq!(least_squares.worst_equation(), Some(2));
+        assert!((least_squares.residuals()[2] + 2.5).abs() < 1e-12);
+        assert!((least_squares.residual_norm() - 7.5f64.sqrt()).abs() < 1e-12);
+
+        // A line 'y = a * t + b' through (0, 1), (1, 3), (2, 5), (3, 7)
+        let mut system = LinearSystem::new();
+        for (t, y) in [(0., 1.), (1., 3.), (2., 5.), (3., 7.)] {
+            system.add_equation(setup.x() * t + setup.y(), y);
+        }
+        let least_squares = system.solve_least_squares().unwrap();
+        Setup::check(&setup.x, 2.);
+        Setup::check(&setup.y, 1.);
+        assert!(least_squares.residual_norm() < 1e-12);
+
+        // Consistent equations give the same values as 'solve'
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.y() + setup.z(), 5.);
+        system.add_equation(setup.x() + setup.z(), 4.);
+        system.add_equation(setup.x() + setup.y(), 3.);
+        system.solve_least_squares().unwrap();
+        Setup::check(&setup.x, 1.);
+        Setup::check(&setup.y, 2.);
+        Setup::check(&setup.z, 3.);
+
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.x() + setup.y(), 3.);
+        system.add_equation(setup.x() * 2. + setup.y() * 2., 5.);
+        system.add_equation(setup.x() * 3. + setup.y() * 3., 9.);
+        assert_eq!(system.solve_least_squares(), Err(Error::SingularSystem));
+        let mut system = LinearSystem::new();
+        system.add_equation(setup.x() + setup.y(), 3.);
+        assert_eq!(system.solve_least_squares(), Err(Error::SingularSystem));
+    }
+}
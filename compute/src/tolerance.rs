@@ -0,0 +1,89 @@
+// How small a coefficient must be to be taken as 0, as some cancellations are inexact with floats
+// A coefficient is negligible if it is at most 'absolute + relative * scale', 'scale' being the size of the terms it
+// results from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    relative: f64,
+    absolute: f64,
+}
+
+impl Tolerance {
+    // Only exact zeros are negligible, for exact scalars
+    pub const EXACT: Tolerance = Tolerance {
+        relative: 0.,
+        absolute: 0.,
+    };
+
+    // Panics if a tolerance is negative
+    pub fn new(relative: f64, absolute: f64) -> Self {
+        assert!(relative >= 0. && absolute >= 0., "tolerances must be positive: {}, {}", relative, absolute);
+        Self { relative, absolute }
+    }
+
+    pub fn relative(relative: f64) -> Self {
+        Self::new(relative, 0.)
+    }
+
+    pub fn absolute(absolute: f64) -> Self {
+        Self::new(0., absolute)
+    }
+
+    pub fn is_negligible(&self, magnitude: f64, scale: f64) -> bool {
+        magnitude <= self.absolute + self.relative * scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::element::Element;
+    use crate::error::Error;
+    use crate::rational::Rational;
+    use crate::roots::Roots;
+
+    #[test]
+    fn test_contradictions_and_identities() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = || Element::new_unknown(rc.clone());
+
+        assert_eq!((x() + 1.).equals(x() + 2.).solve(), Err(Error::NoSolution));
+        assert_eq!((x() + 1.).equals(x() + 1.).solve(), Err(Error::InfiniteSolutions));
+        assert_eq!((x() * x()).equals(x() * x() - 3.).solve(), Err(Error::NoSolution));
+        assert_eq!(((x() + 1.) / (x() + 1.)).equals(1.).solve(), Err(Error::InfiniteSolutions));
+        assert_eq!(Element::new_known(1.).equals(2.).solve(), Err(Error::NoUnkown));
+
+        // Cancellations left inexact by rounding errors
+        let relation = (x() * 0.1 + x() * 0.2).equals(x() * 0.3 + 1.);
+        assert_eq!(relation.solve(), Err(Error::NoSolution));
+        let relation = (x() * 0.1 + x() * 0.2 + 0.3).equals(x() * 0.3 + 0.1 + 0.2);
+        assert_eq!(relation.solve(), Err(Error::InfiniteSolutions));
+        let relation = (x() * 0.1 + x() * 0.2).equals(x() * 0.3 + 1.).with_tolerance(Tolerance::EXACT);
+        assert_eq!(relation.solve().unwrap().roots().len(), 1);
+    }
+
+    #[test]
+    fn test_configured_tolerance() {
+        let rc = Rc::new(RefCell::new(0.));
+        let x = || Element::new_unknown(rc.clone());
+
+        // x + 1 = 1.001 * x + 1 only holds for x = 0, unless a relative error of 0.1% is tolerated
+        let relation = (x() + 1.).equals(x() * 1.001 + 1.);
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(0.));
+        let relation = relation.with_tolerance(Tolerance::relative(1e-2));
+        assert_eq!(relation.solve(), Err(Error::InfiniteSolutions));
+        let relation = (x() + 1.).equals(x() + 1.5).with_tolerance(Tolerance::absolute(1.));
+        assert_eq!(relation.solve(), Err(Error::InfiniteSolutions));
+
+        // Exact scalars only cancel exact zeros
+        let rc = Rc::new(RefCell::new(Rational::ZERO));
+        let x = || Element::new_unknown(rc.clone());
+        let tiny = Rational::new(1, 1_000_000_000_000_000);
+        let relation = (x() * (Rational::ONE + tiny)).equals(x() + tiny);
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(Rational::ONE));
+        assert_eq!((x() + tiny).equals(x()).solve(), Err(Error::NoSolution));
+    }
+}
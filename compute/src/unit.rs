@@ -0,0 +1,138 @@
+use std::ops::{Div, Mul};
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::rational::Rational;
+use crate::scalar::Scalar;
+
+pub use units::Dimension;
+
+// A unit like 'km/h', i.e. a dimension and the factor converting its values to SI units
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit {
+    factor: Rational,
+    dimension: Dimension,
+}
+
+impl Unit {
+    pub const NONE: Unit = Unit::si(Dimension::NONE);
+
+    // The SI unit of a dimension
+    pub const fn si(dimension: Dimension) -> Self {
+        Self {
+            factor: Rational::ONE,
+            dimension,
+        }
+    }
+
+    pub fn parse(unit: &str) -> Result<Self, Error> {
+        let unit = units::Unit::parse(unit).ok_or(Error::InvalidUnit)?;
+        let (numerator, denominator) = unit.factor();
+        Ok(Self {
+            factor: Rational::new(numerator, denominator),
+            dimension: unit.dimension(),
+        })
+    }
+
+    pub fn factor(&self) -> Rational {
+        self.factor
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    // The factor converting values in this unit to SI units, as a scalar
+    pub(crate) fn scalar_factor<S: Scalar>(&self) -> Result<S, Error> {
+        let numerator = S::from_f64(self.factor.numerator() as f64);
+        let denominator = S::from_f64(self.factor.denominator() as f64);
+        numerator
+            .zip(denominator)
+            .map(|(numerator, denominator)| numerator / denominator)
+            .ok_or(Error::InvalidUnit)
+    }
+}
+
+impl Mul for Unit {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            factor: self.factor * rhs.factor,
+            dimension: self.dimension * rhs.dimension,
+        }
+    }
+}
+
+impl Div for Unit {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Self {
+            factor: self.factor / rhs.factor,
+            dimension: self.dimension / rhs.dimension,
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = Error;
+
+    fn from_str(unit: &str) -> Result<Self, Error> {
+        Self::parse(unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::equation::EquationElement;
+
+    #[test]
+    fn test_parse_units() {
+        let speed = Dimension::LENGTH / Dimension::TIME;
+        assert_eq!(Unit::parse("m/s"), Ok(Unit::si(speed)));
+        assert_eq!(Unit::parse("km/h").map(|unit| unit.factor()), Ok(Rational::new(5, 18)));
+        assert_eq!(Unit::parse("km / h").map(|unit| unit.dimension()), Ok(speed));
+        assert_eq!(Unit::parse("mm").map(|unit| unit.factor()), Ok(Rational::new(1, 1000)));
+        assert_eq!(Unit::parse("min").map(|unit| unit.factor()), Ok(Rational::integer(60)));
+        assert_eq!(Unit::parse("ms").map(|unit| unit.factor()), Ok(Rational::new(1, 1000)));
+        assert_eq!(Unit::parse("kg*m/s^2"), Unit::parse("N"));
+        assert_eq!(Unit::parse("kN").map(|unit| unit.factor()), Ok(Rational::integer(1000)));
+        assert_eq!(Unit::parse("mg").map(|unit| unit.factor()), Ok(Rational::new(1, 1_000_000)));
+        assert_eq!(Unit::parse("cm^2").map(|unit| unit.factor()), Ok(Rational::new(1, 10_000)));
+        assert_eq!(Unit::parse("1/s"), Unit::parse("Hz"));
+        assert_eq!(Unit::parse("s^-1"), Unit::parse("Hz"));
+        assert_eq!(Unit::parse("J/s"), Unit::parse("W"));
+        assert_eq!("V*A".parse::<Unit>(), Unit::parse("W"));
+
+        assert_eq!(Unit::parse(""), Err(Error::InvalidUnit));
+        assert_eq!(Unit::parse("furlong"), Err(Error::InvalidUnit));
+        assert_eq!(Unit::parse("km/"), Err(Error::InvalidUnit));
+        assert_eq!(Unit::parse("kmin"), Err(Error::InvalidUnit));
+        assert_eq!(Unit::parse("m^x"), Err(Error::InvalidUnit));
+    }
+
+    #[test]
+    fn test_dimensional_analysis() {
+        let distance = EquationElement::<f64>::known(3.).with_unit("km").unwrap();
+        let speed = EquationElement::known(60.).with_unit("km/h").unwrap();
+        let time = EquationElement::unknown().with_unit("min").unwrap();
+
+        // The solution comes back in the unit of the unknown
+        let relation = speed.clone().equals(distance.clone() / time.clone());
+        assert_eq!(relation.solve().unwrap().roots().single().map(|x| x.round()), Some(3.));
+        let relation = (2. * distance.clone()).equals(speed.clone() * time.clone() + 3000.);
+        assert_eq!(relation.solve().unwrap().roots().single().map(|x| x.round()), Some(3.));
+
+        let relation = speed.clone().equals(distance.clone() * time.clone());
+        assert_eq!(relation.solve(), Err(Error::DimensionMismatch));
+        let relation = (distance.clone() + time.clone()).equals(0.);
+        assert_eq!(relation.solve(), Err(Error::DimensionMismatch));
+        let relation = (distance / time).equals(1. / speed);
+        assert_eq!(relation.solve(), Err(Error::DimensionMismatch));
+
+        assert_eq!(EquationElement::<f64>::unknown().with_unit("parsec").err(), Some(Error::InvalidUnit));
+    }
+}
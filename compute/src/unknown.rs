@@ -2,25 +2,40 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::error::Error;
+use crate::scalar::Scalar;
+
+pub type UnknownId = usize;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Unknown {
+pub struct Unknown<S = f64> {
     pub status: Result<(), Error>,
-    pub unknown: Rc<RefCell<f64>>,
+    pub unknown: Rc<RefCell<S>>,
+    // The index of the derivatives of a known lowered as an unknown, to differentiate the roots with respect to it
+    pub(crate) parameter: Option<usize>,
 }
 
-impl Unknown {
+impl<S: Scalar> Unknown<S> {
     pub(crate) fn new() -> Self {
+        Self::new_with_value(Rc::new(RefCell::new(S::zero())))
+    }
+
+    pub(crate) fn new_with_value(unknown: Rc<RefCell<S>>) -> Self {
         Unknown {
             status: Ok(()),
-            unknown: Rc::new(RefCell::new(0.)),
+            unknown,
+            parameter: None,
         }
     }
 
-    pub(crate) fn new_with_value(unknown: Rc<RefCell<f64>>) -> Self {
+    pub(crate) fn parameter(value: S, index: usize) -> Self {
         Unknown {
-            status: Ok(()),
-            unknown,
+            parameter: Some(index),
+            ..Self::new_with_value(Rc::new(RefCell::new(value)))
         }
     }
+
+    // Two unknowns are the same if they share the same value
+    pub fn id(&self) -> UnknownId {
+        Rc::as_ptr(&self.unknown) as UnknownId
+    }
 }
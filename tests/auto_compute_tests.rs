@@ -6,11 +6,24 @@ extern crate compute_macro;
 
 #[cfg(test)]
 mod tests {
-    use compute::equation::{EquationAutoCompute, EquationElement};
+    use compute::complex::Complex;
+    use compute::dual::Dual;
+    use compute::equation::{EquationAutoCompute, EquationElement, Lowering};
+    use compute::error::Error;
+    use compute::expression::Expression;
+    use compute::inequality::Bounds;
+    use compute::interval::Interval;
+    use compute::integer::Integer;
+    use compute::modular::Modular;
+    use compute::nonlinear::NonlinearSystem;
+    use compute::rational::Rational;
+    use compute::relation::{Method, Relation};
+    use compute::roots::Roots;
 
     use super::*;
 
     use std::cell::RefCell;
+    use std::ops::Bound;
     use std::rc::Rc;
 
     struct Setup {
@@ -38,8 +51,9 @@ mod tests {
 
         let element2 = 2. * Element::new_unknown(setup.rc.clone()) + Element::new_known(4.);
         let sum = 3. * Element::new_unknown(setup.rc.clone()) + Element::new_known(7.);
-        let _ = element2 == sum;
-        assert_eq!(*setup.rc.borrow(), -3.);
+        let solution = element2.equals(sum).solve().unwrap();
+        assert_eq!(solution.roots(), &Roots::from(-3.));
+        assert_eq!(*setup.rc.borrow(), 0.);
     }
 
     #[derive(Equation)]
@@ -52,8 +66,8 @@ mod tests {
     }
 
     impl EquationAutoCompute for MyEquation {
-        fn auto_compute(&self) -> bool {
-            self.element1 == self.element2.clone() + self.element3.clone()
+        fn auto_compute(&self) -> Relation {
+            (self.element2.clone() + self.element3.clone()).equals(self.element1)
         }
     }
 
@@ -84,6 +98,756 @@ mod tests {
             element2: EquationElement::unknown(),
             element3: EquationElement::known(30.),
         };
-        assert_eq!(my_equation.compute(), Ok(70.));
+        assert_eq!(my_equation.compute(), Ok(Roots::from(70.)));
+    }
+
+    #[test]
+    fn equation_relation_test() {
+        let my_equation = MyEquation {
+            element1: 100.,
+            element2: EquationElement::unknown(),
+            element3: EquationElement::known(30.),
+        };
+        let relation = my_equation.auto_compute();
+        assert_eq!(relation.rhs(), &Element::new_known(100.));
+
+        let solution = relation.solve().unwrap();
+        assert_eq!(solution.roots(), &Roots::from(70.));
+        assert_eq!(relation.solve(), Ok(solution));
+        assert_eq!(my_equation.element2.get_unknown_value(), Ok(0.));
+        assert_eq!(my_equation.element3.get_unknown_value(), Err(Error::NoUnkown));
+    }
+
+    #[derive(Equation)]
+    struct SquareEquation {
+        #[variable]
+        side: EquationElement,
+        #[variable]
+        area: EquationElement,
+    }
+
+    impl EquationAutoCompute for SquareEquation {
+        fn auto_compute(&self) -> Relation {
+            self.area.clone().equals(self.side.clone() * self.side.clone())
+        }
+    }
+
+    #[test]
+    fn square_equation_test() {
+        let square_equation = SquareEquation {
+            side: EquationElement::unknown(),
+            area: EquationElement::known(16.),
+        };
+        assert_eq!(square_equation.compute(), Ok(Roots::from(vec![-4., 4.])));
+
+        let square_equation = SquareEquation {
+            side: EquationElement::known(3.),
+            area: EquationElement::unknown(),
+        };
+        assert_eq!(square_equation.compute(), Ok(Roots::from(9.)));
+    }
+
+    #[derive(Equation)]
+    struct SpeedEquation {
+        #[variable]
+        speed: EquationElement,
+        #[variable]
+        distance: EquationElement,
+        #[variable]
+        time: EquationElement,
+    }
+
+    impl EquationAutoCompute for SpeedEquation {
+        fn auto_compute(&self) -> Relation {
+            self.speed.clone().equals(self.distance.clone() / self.time.clone())
+        }
+    }
+
+    #[test]
+    fn speed_equation_test() {
+        let speed_equation = SpeedEquation {
+            speed: EquationElement::known(20.),
+            distance: EquationElement::known(100.),
+            time: EquationElement::unknown(),
+        };
+        assert_eq!(speed_equation.compute(), Ok(Roots::from(5.)));
+
+        let speed_equation = SpeedEquation {
+            speed: EquationElement::known(0.),
+            distance: EquationElement::known(100.),
+            time: EquationElement::unknown(),
+        };
+        assert_eq!(speed_equation.compute(), Ok(Roots::default()));
+    }
+
+    #[test]
+    fn write_equation_test() {
+        let speed_equation = SpeedEquation {
+            speed: EquationElement::known(20.),
+            distance: EquationElement::known(100.),
+            time: EquationElement::unknown(),
+        };
+        // The unknown is named after its field in the relation, the field itself being left unnamed
+        let relation = speed_equation.relation();
+        assert_eq!(relation.to_string(), "20 = 100 / time");
+        assert_eq!(relation.to_latex(), "20 = \\frac{100}{\\mathrm{time}}");
+        assert!(relation.to_mathml().contains("<mi>time</mi>"));
+        assert_eq!(speed_equation.time.name(), None);
+        assert_eq!(speed_equation.relation_for(SpeedEquationVar::Distance).to_string(), "20 = distance / time");
+    }
+
+    #[derive(Equation)]
+    struct BalanceEquation {
+        #[variable]
+        x: EquationElement,
+        #[variable]
+        y: EquationElement,
+    }
+
+    impl EquationAutoCompute for BalanceEquation {
+        fn auto_compute(&self) -> Relation {
+            (self.x.clone() + self.y.clone()).equals(self.x.clone() + 2.)
+        }
+    }
+
+    #[test]
+    fn cancelled_unknown_test() {
+        let balance = BalanceEquation {
+            x: EquationElement::unknown(),
+            y: EquationElement::known(3.),
+        };
+        assert_eq!(balance.compute(), Err(Error::NoSolution));
+
+        let balance = BalanceEquation {
+            x: EquationElement::unknown(),
+            y: EquationElement::known(2.),
+        };
+        assert_eq!(balance.compute(), Err(Error::InfiniteSolutions));
+    }
+
+    #[derive(Equation)]
+    struct SinglePrecisionEquation {
+        #[variable]
+        side: EquationElement<f32>,
+        #[variable]
+        area: EquationElement<f32>,
+    }
+
+    impl EquationAutoCompute<f32> for SinglePrecisionEquation {
+        fn auto_compute(&self) -> Relation<f32> {
+            self.area.clone().equals(self.side.clone() * self.side.clone())
+        }
+    }
+
+    #[test]
+    fn single_precision_equation_test() {
+        let equation = SinglePrecisionEquation {
+            side: EquationElement::unknown(),
+            area: EquationElement::known(2.25f32),
+        };
+        assert_eq!(equation.compute(), Ok(Roots::from(vec![-1.5f32, 1.5])));
+
+        let equation = SinglePrecisionEquation {
+            side: EquationElement::known(3f32),
+            area: EquationElement::unknown(),
+        };
+        assert_eq!(equation.compute(), Ok(Roots::from(9f32)));
+    }
+
+    #[derive(Equation)]
+    struct ExactEquation {
+        #[variable]
+        x: EquationElement<Rational>,
+        #[variable]
+        third: EquationElement<Rational>,
+    }
+
+    impl EquationAutoCompute<Rational> for ExactEquation {
+        fn auto_compute(&self) -> Relation<Rational> {
+            (self.x.clone() / Rational::from(3)).equals(self.third.clone())
+        }
+    }
+
+    #[test]
+    fn exact_equation_test() {
+        let equation = ExactEquation {
+            x: EquationElement::unknown(),
+            third: EquationElement::known(Rational::new(1, 7)),
+        };
+        let roots = equation.compute().unwrap();
+        assert_eq!(roots, Roots::from(Rational::new(3, 7)));
+        assert_eq!(roots.single().map(Rational::to_f64), Some(3. / 7.));
+    }
+
+    #[derive(Equation)]
+    struct SensorEquation {
+        #[variable]
+        total: EquationElement<Interval>,
+        #[variable]
+        first: EquationElement<Interval>,
+        #[variable]
+        second: EquationElement<Interval>,
+    }
+
+    impl EquationAutoCompute<Interval> for SensorEquation {
+        fn auto_compute(&self) -> Relation<Interval> {
+            self.total.clone().equals(self.first.clone() + self.second.clone())
+        }
+    }
+
+    #[test]
+    fn interval_equation_test() {
+        let equation = SensorEquation {
+            total: EquationElement::known(100.),
+            first: EquationElement::known(Interval::with_tolerance(3., 0.05)),
+            second: EquationElement::unknown(),
+        };
+        let roots = equation.compute().unwrap();
+        let second = roots.single().unwrap();
+        assert!(second.lower() <= 96.95 && second.upper() >= 97.05);
+        assert!(second.width() < 0.1 + 1e-12);
+    }
+
+    #[derive(Equation)]
+    struct TripEquation {
+        #[variable(unit = "km/h")]
+        speed: EquationElement,
+        #[variable(unit = "km")]
+        distance: EquationElement,
+        #[variable(unit = "min")]
+        time: EquationElement,
+    }
+
+    impl EquationAutoCompute for TripEquation {
+        fn auto_compute(&self) -> Relation {
+            self.speed.clone().equals(self.distance.clone() / self.time.clone())
+        }
+    }
+
+    #[test]
+    fn units_equation_test() {
+        let trip = TripEquation {
+            speed: EquationElement::unknown(),
+            distance: EquationElement::known(3.),
+            time: EquationElement::known(2.),
+        };
+        let speed = *trip.compute().unwrap().single().unwrap();
+        assert!((speed - 90.).abs() < 1e-12);
+
+        // The unit given to an element takes precedence over the one of its field
+        let trip = TripEquation {
+            speed: EquationElement::known(90.),
+            distance: EquationElement::known(3000.).with_unit("m").unwrap(),
+            time: EquationElement::unknown(),
+        };
+        let time = *trip.compute().unwrap().single().unwrap();
+        assert!((time - 2.).abs() < 1e-12);
+    }
+
+    #[derive(Equation)]
+    struct DecayEquation {
+        #[variable]
+        remaining: EquationElement,
+        #[variable]
+        initial: EquationElement,
+        #[variable]
+        time: EquationElement,
+        half_life: f64,
+    }
+
+    impl EquationAutoCompute for DecayEquation {
+        fn auto_compute(&self) -> Relation {
+            let rate = 2f64.ln() / self.half_life;
+            let decay = (-rate * self.time.clone()).exp();
+            self.remaining.clone().equals(self.initial.clone() * decay)
+        }
+    }
+
+    #[test]
+    fn transcendental_equation_test() {
+        let decay = DecayEquation {
+            remaining: EquationElement::known(25.),
+            initial: EquationElement::known(100.),
+            time: EquationElement::unknown(),
+            half_life: 10.,
+        };
+        let time = *decay.compute().unwrap().single().unwrap();
+        assert!((time - 20.).abs() < 1e-9);
+
+        let solution = decay.auto_compute().solve().unwrap();
+        assert_eq!(solution.method(), Method::Analytic);
+    }
+
+    #[derive(Equation)]
+    struct ProjectileEquation {
+        #[variable]
+        range: EquationElement<Dual>,
+        #[variable(unit = "m/s")]
+        speed: EquationElement<Dual>,
+        #[variable]
+        gravity: EquationElement<Dual>,
+    }
+
+    impl EquationAutoCompute<Dual> for ProjectileEquation {
+        fn auto_compute(&self) -> Relation<Dual> {
+            // range = speed^2 / gravity, at 45°
+            let speed = self.speed.clone();
+            self.range.clone().equals(speed.clone() * speed / self.gravity.clone())
+        }
+    }
+
+    #[test]
+    fn sensitivities_test() {
+        let projectile = ProjectileEquation {
+            range: EquationElement::unknown(),
+            speed: EquationElement::known(20.),
+            gravity: EquationElement::known(10.),
+        };
+        let sensitivities = projectile.compute_with_sensitivities().unwrap();
+        let sensitivities = sensitivities.single().unwrap();
+        assert!((sensitivities.value() - 40.).abs() < 1e-9);
+        // d(range)/d(speed) = 2 * speed / gravity, d(range)/d(gravity) = -speed^2 / gravity^2
+        assert!((sensitivities.derivative("speed").unwrap() - 4.).abs() < 1e-9);
+        assert!((sensitivities.derivative("gravity").unwrap() + 4.).abs() < 1e-9);
+        assert_eq!(sensitivities.derivative("range"), None);
+        // The knowns are only differentiated in the relation, the fields are left as they were
+        assert!(!projectile.speed.is_unknown() && !projectile.gravity.is_unknown());
+
+        // The speed is found from the range
+        let projectile = ProjectileEquation {
+            range: EquationElement::known(40.),
+            speed: EquationElement::unknown(),
+            gravity: EquationElement::known(10.),
+        };
+        let roots = projectile.compute_with_sensitivities().unwrap();
+        let speed = roots.iter().find(|root| root.value() > 0.).unwrap();
+        assert!((speed.value() - 20.).abs() < 1e-9);
+        assert!((speed.derivative("range").unwrap() - 0.25).abs() < 1e-9);
+        assert_eq!(projectile.compute().unwrap().iter().map(|root| root.derivatives().len()).max(), Some(0));
+    }
+
+    #[derive(Equation)]
+    #[equation("force = mass * acceleration")]
+    struct ForceEquation {
+        #[variable]
+        force: Option<f64>,
+        #[variable]
+        mass: f64,
+        #[variable]
+        acceleration: EquationElement,
+    }
+
+    #[test]
+    fn f64_sensitivities_test() {
+        let equation = ForceEquation {
+            force: Some(30.),
+            mass: 2.,
+            acceleration: EquationElement::unknown(),
+        };
+        let roots = equation.compute_with_sensitivities().unwrap();
+        let acceleration = roots.single().unwrap();
+        assert!((acceleration.value() - 15.).abs() < 1e-9);
+        // d(acceleration)/d(force) = 1 / mass, d(acceleration)/d(mass) = -force / mass^2
+        assert!((acceleration.derivative("force").unwrap() - 0.5).abs() < 1e-9);
+        assert!((acceleration.derivative("mass").unwrap() + 7.5).abs() < 1e-9);
+        assert_eq!(acceleration.derivative("acceleration"), None);
+    }
+
+    #[derive(Equation)]
+    struct ImpedanceEquation {
+        #[variable]
+        voltage: EquationElement<Complex>,
+        #[variable]
+        current: EquationElement<Complex>,
+        #[variable]
+        impedance: EquationElement<Complex>,
+    }
+
+    impl EquationAutoCompute<Complex> for ImpedanceEquation {
+        fn auto_compute(&self) -> Relation<Complex> {
+            self.voltage.clone().equals(self.current.clone() * self.impedance.clone())
+        }
+    }
+
+    #[test]
+    fn complex_test() {
+        let circuit = ImpedanceEquation {
+            voltage: EquationElement::known(10.),
+            current: EquationElement::unknown(),
+            impedance: EquationElement::known(Complex::new(3., 4.)),
+        };
+        assert_eq!(circuit.compute(), Ok(Roots::from(Complex::new(1.2, -1.6))));
+        assert_eq!(circuit.relation().to_string(), "10 = (3+4i)·current");
+    }
+
+    #[derive(Equation)]
+    #[equation("element1 = element2 + element3")]
+    struct WrittenEquation {
+        element1: f64,
+        #[variable]
+        element2: EquationElement,
+        #[variable]
+        element3: EquationElement,
+    }
+
+    #[derive(Equation)]
+    #[equation("speed = sqrt(2 * gravity * height)")]
+    struct FallEquation {
+        #[variable(unit = "m/s")]
+        speed: EquationElement,
+        #[variable(unit = "m")]
+        height: EquationElement,
+        gravity: f64,
+    }
+
+    #[test]
+    fn equation_attribute_test() {
+        let my_equation = WrittenEquation {
+            element1: 100.,
+            element2: EquationElement::unknown(),
+            element3: EquationElement::known(30.),
+        };
+        assert_eq!(my_equation.compute(), Ok(Roots::from(70.)));
+        assert_eq!(my_equation.relation().to_string(), "100 = element2 + element3");
+
+        let fall = FallEquation {
+            speed: EquationElement::known(14.),
+            height: EquationElement::unknown(),
+            gravity: 9.8,
+        };
+        let height = *fall.compute().unwrap().single().unwrap();
+        assert!((height - 10.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solve_for_test() {
+        assert_eq!(MyEquation::solve_for_element2(100., 30.), Ok(70.));
+        assert_eq!(MyEquation::solve_for_element3(100., 70.), Ok(30.));
+        assert_eq!(SquareEquation::solve_for_area(3.), Ok(9.));
+        assert_eq!(SquareEquation::solve_for_side(9.), Err(Error::SeveralRoots));
+
+        let height = FallEquation::solve_for_height(14., 9.8).unwrap();
+        assert!((height - 10.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn variables_test() {
+        assert_eq!(MyEquation::variables(), &[MyEquationVar::Element2, MyEquationVar::Element3]);
+        assert_eq!(MyEquationVar::Element3.name(), "element3");
+        let variable = FallEquation::variables().iter().find(|variable| variable.name() == "height");
+        assert_eq!(variable, Some(&FallEquationVar::Height));
+
+        let my_equation = MyEquation {
+            element1: 100.,
+            element2: EquationElement::known(70.),
+            element3: EquationElement::known(30.),
+        };
+        assert_eq!(my_equation.solve_for(MyEquationVar::Element2), Ok(Roots::from(70.)));
+        assert_eq!(my_equation.solve_for(MyEquationVar::Element3), Ok(Roots::from(30.)));
+
+        let my_equation = MyEquation {
+            element1: 100.,
+            element2: EquationElement::known(70.),
+            element3: EquationElement::unknown(),
+        };
+        assert_eq!(my_equation.compute_with_variable(), Ok((MyEquationVar::Element3, Roots::from(30.))));
+        assert_eq!(my_equation.solve_for(MyEquationVar::Element2), Err(Error::SeveralUnknown));
+    }
+
+    // A field that can't be cloned, the struct never being built again to choose the unknown
+    struct Label(String);
+
+    #[derive(Equation)]
+    struct LabelledEquation {
+        label: Label,
+        #[variable]
+        total: EquationElement,
+        #[variable]
+        part: EquationElement,
+    }
+
+    impl EquationAutoCompute for LabelledEquation {
+        fn auto_compute(&self) -> Relation {
+            self.total.clone().equals(self.part.clone() * 2.)
+        }
+    }
+
+    #[test]
+    fn non_clone_field_test() {
+        let equation = LabelledEquation {
+            label: Label("double".to_string()),
+            total: EquationElement::known(10.),
+            part: EquationElement::known(5.),
+        };
+        assert_eq!(equation.solve_for(LabelledEquationVar::Part), Ok(Roots::from(5.)));
+        assert_eq!(equation.solve_for(LabelledEquationVar::Total), Ok(Roots::from(10.)));
+        // The fields are left as they were
+        assert!(!equation.part.is_unknown() && !equation.total.is_unknown());
+        assert_eq!(equation.label.0, "double");
+    }
+
+    #[test]
+    fn lowering_test() {
+        let field = EquationElement::known(5.);
+        let copy = EquationElement::unknown().with_name("copy");
+        let relation = Lowering::new().with_field(&field, copy).apply(|| field.clone().equals(10.));
+        assert_eq!(relation.to_string(), "copy = 10");
+        assert_eq!(relation.solve().unwrap().roots(), &Roots::from(10.));
+        let lowering = Lowering::new().with_field(&field, EquationElement::unknown().with_name("copy"));
+        let relation = lowering.apply(|| Relation::from_expressions(Expression::from(field.clone()), Expression::from(10.)));
+        assert_eq!(relation.to_string(), "copy = 10");
+
+        // The field is lowered as itself after 'apply', even if it panicked
+        let lowering = Lowering::new().with_field(&field, EquationElement::unknown());
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| lowering.apply(|| panic!("writing"))));
+        assert!(panicked.is_err());
+        assert_eq!(field.clone().equals(10.).solve(), Err(Error::NoUnkown));
+    }
+
+    #[test]
+    fn builder_test() {
+        assert_eq!(MyEquation::builder().element1(100.).element3(30.).solve(), Ok(Roots::from(70.)));
+        assert_eq!(MyEquation::builder().element1(100.).element2(30).solve(), Ok(Roots::from(70.)));
+        assert_eq!(MyEquation::builder().element1(100.).solve(), Err(Error::SeveralUnknown));
+        let known = MyEquation::builder().element1(100.).element2(70.).element3(30.);
+        assert_eq!(known.solve(), Err(Error::NoUnkown));
+        assert_eq!(MyEquation::builder().element3(30.).solve(), Err(Error::MissingValue));
+
+        let fall = FallEquation::builder().speed(14.).gravity(9.8).build().unwrap();
+        assert!(fall.height.is_unknown());
+        assert_eq!(fall.compute_with_variable().map(|(variable, _)| variable), Ok(FallEquationVar::Height));
+    }
+
+    #[derive(Equation)]
+    #[equation("total = count * size + offset")]
+    struct StorageEquation {
+        #[variable]
+        total: Option<f64>,
+        #[variable]
+        count: u8,
+        #[variable]
+        size: u32,
+        #[variable]
+        offset: i32,
+    }
+
+    // Counts too large for an f64 stay exact with integers
+    #[derive(Equation)]
+    #[equation("total = count * size")]
+    struct LargeStorageEquation {
+        #[variable]
+        total: EquationElement<Integer>,
+        #[variable]
+        count: usize,
+        #[variable]
+        size: u64,
+    }
+
+    #[test]
+    fn integer_fields_test() {
+        let storage = StorageEquation {
+            total: None,
+            count: 3,
+            size: 512,
+            offset: -36,
+        };
+        assert_eq!(storage.compute(), Ok(Roots::from(1500.)));
+        assert_eq!(StorageEquation::solve_for_offset(1500., 3, 512), Ok(-36.));
+        assert_eq!(StorageEquation::builder().total(1500.).count(3).offset(-36).solve(), Ok(Roots::from(512.)));
+
+        let storage = LargeStorageEquation {
+            total: EquationElement::unknown(),
+            count: 3,
+            size: (1 << 60) + 1,
+        };
+        assert_eq!(storage.compute(), Ok(Roots::from(Integer::new(3 * ((1 << 60) + 1)))));
+    }
+
+    mod geometry {
+        use compute::equation::EquationElement;
+
+        #[derive(Equation)]
+        #[equation("area = width * height")]
+        pub struct RectangleEquation {
+            #[variable]
+            area: EquationElement,
+            #[variable]
+            width: EquationElement,
+            #[variable]
+            height: EquationElement,
+        }
+    }
+
+    #[test]
+    fn public_builder_test() {
+        let roots = geometry::RectangleEquation::builder().area(12.).width(4.).solve();
+        assert_eq!(roots, Ok(Roots::from(3.)));
+        assert!(geometry::RectangleEquation::builder().area(12.).build().is_err());
+    }
+
+    #[derive(Equation)]
+    #[equation("distance = speed * time")]
+    struct WalkEquation {
+        #[variable(unit = "m")]
+        distance: Option<f64>,
+        #[variable(unit = "m/s")]
+        speed: Option<f64>,
+        #[variable(unit = "s")]
+        time: u32,
+    }
+
+    #[test]
+    fn plain_fields_test() {
+        let mut walk = WalkEquation {
+            distance: Some(300.),
+            speed: None,
+            time: 60,
+        };
+        assert_eq!(walk.compute_with_variable(), Ok((WalkEquationVar::Speed, Roots::from(5.))));
+        assert_eq!(walk.solve_in_place(), Ok(()));
+        assert_eq!(walk.speed, Some(5.));
+        assert_eq!(walk.compute(), Err(Error::NoUnkown));
+        assert_eq!(walk.solve_for(WalkEquationVar::Time), Ok(Roots::from(60.)));
+
+        assert_eq!(WalkEquation::solve_for_time(300., 5.), Ok(60.));
+        assert_eq!(WalkEquation::builder().distance(300.).speed(5).solve(), Ok(Roots::from(60.)));
+        assert_eq!(WalkEquation::builder().distance(300.).speed(5.).build().err(), Some(Error::MissingValue));
+
+        let mut my_equation = MyEquation {
+            element1: 100.,
+            element2: EquationElement::unknown(),
+            element3: EquationElement::known(30.),
+        };
+        assert_eq!(my_equation.solve_in_place(), Ok(()));
+        assert!(!my_equation.element2.is_unknown());
+        assert_eq!(my_equation.solve_for(MyEquationVar::Element2), Ok(Roots::from(70.)));
+    }
+
+    #[test]
+    fn linear_system_test() {
+        use compute::system::LinearSystem;
+
+        // element1 = element2 + element3, element2 = 2 * element3
+        let element2 = EquationElement::unknown();
+        let element3 = EquationElement::unknown();
+        let mut system = LinearSystem::new();
+        system.add_equation(90., element2.clone() + element3.clone());
+        system.add_relation(element2.clone().equals(2. * element3.clone()));
+        assert_eq!(system.solve(), Ok(()));
+        assert_eq!(element2.get_unknown_value(), Ok(60.));
+        assert_eq!(element3.get_unknown_value(), Ok(30.));
+    }
+
+    #[test]
+    fn nonlinear_system_test() {
+        let x = EquationElement::<f64>::unknown().with_name("x");
+        let y = EquationElement::unknown().with_name("y");
+
+        // The Jacobian of 'x * y = 2, x + 2 * y = 5' is singular at the guesses, a Levenberg–Marquardt step leaves
+        // the line 'x = 2 * y' before Newton's steps converge
+        let mut system = NonlinearSystem::new();
+        system.add_relation((x.clone() * y.clone()).equals(2.));
+        system.add_relation((x.clone() + y.clone() * 2.).equals(5.));
+        system.set_initial_guess(&x, 2.).unwrap();
+        system.set_initial_guess(&y, 1.).unwrap();
+        let solution = system.solve().unwrap();
+        assert!(solution.converged());
+        let (x, y) = (x.get_unknown_value().unwrap(), y.get_unknown_value().unwrap());
+        assert!((x * y - 2.).abs() < 1e-9 && (x + 2. * y - 5.).abs() < 1e-9, "({}, {})", x, y);
+    }
+
+    // Boxes are shipped on pallets, two layers of 'boxes_per_layer' each
+    #[derive(Equation)]
+    struct PalletEquation {
+        #[variable]
+        pallets: EquationElement<Integer>,
+        #[variable]
+        boxes: EquationElement<Integer>,
+        boxes_per_layer: i64,
+    }
+
+    impl EquationAutoCompute<Integer> for PalletEquation {
+        fn auto_compute(&self) -> Relation<Integer> {
+            let layers = self.boxes.clone() / Integer::from(self.boxes_per_layer);
+            self.pallets.clone().equals(layers / Integer::from(2))
+        }
+    }
+
+    #[test]
+    fn integer_equation_test() {
+        let equation = PalletEquation {
+            pallets: EquationElement::known(Integer::from(3)),
+            boxes: EquationElement::unknown(),
+            boxes_per_layer: 4,
+        };
+        assert_eq!(equation.compute(), Ok(Roots::from(Integer::from(24))));
+
+        // 13 boxes don't fill whole pallets
+        let equation = PalletEquation {
+            pallets: EquationElement::unknown(),
+            boxes: EquationElement::known(Integer::from(13)),
+            boxes_per_layer: 4,
+        };
+        assert_eq!(equation.compute(), Err(Error::NoSolution));
+    }
+
+    type Mod26 = Modular<26>;
+
+    // An affine cipher, each letter being a residue modulo 26
+    #[derive(Equation)]
+    struct CipherEquation {
+        #[variable]
+        cipher: EquationElement<Mod26>,
+        #[variable]
+        plain: EquationElement<Mod26>,
+        key: i64,
+    }
+
+    impl EquationAutoCompute<Mod26> for CipherEquation {
+        fn auto_compute(&self) -> Relation<Mod26> {
+            self.cipher.clone().equals(self.plain.clone() * Mod26::from(self.key) + Mod26::from(3))
+        }
+    }
+
+    #[test]
+    fn modular_equation_test() {
+        let equation = CipherEquation {
+            cipher: EquationElement::known(Mod26::from(5)),
+            plain: EquationElement::unknown(),
+            key: 7,
+        };
+        assert_eq!(equation.compute(), Ok(Roots::from(Mod26::from(4))));
+
+        // 4 has no inverse modulo 26, so 4x + 3 ≡ 9 has two roots and 4x + 3 ≡ 8 none
+        let equation = CipherEquation {
+            cipher: EquationElement::known(Mod26::from(9)),
+            plain: EquationElement::unknown(),
+            key: 4,
+        };
+        assert_eq!(equation.compute(), Ok(Roots::from(vec![Mod26::from(8), Mod26::from(21)])));
+        let equation = CipherEquation {
+            cipher: EquationElement::known(Mod26::from(8)),
+            plain: EquationElement::unknown(),
+            key: 4,
+        };
+        assert_eq!(equation.compute(), Err(Error::NoSolution));
+    }
+
+    #[test]
+    fn inequality_test() {
+        let budget = EquationElement::known(100.).with_name("budget");
+        let items = EquationElement::unknown().with_name("items");
+
+        // budget - 8 * items ≥ 20, the negative coefficient flipping the comparison into items ≤ 10
+        let inequality = (budget.clone() - items.clone() * 8.).ge(20.);
+        let bounds = inequality.solve().unwrap();
+        assert_eq!(bounds, Bounds::new(Bound::Unbounded, Bound::Included(10.)));
+        assert!(bounds.contains(&10.) && !bounds.contains(&10.5));
+
+        // With at least one item
+        let bounds = inequality.and(items.clone().gt(0.)).solve().unwrap();
+        assert_eq!(bounds.to_string(), "(0, 10]");
+        let never = (budget - items.clone() * 8.).gt(100.).and(items.gt(0.));
+        assert_eq!(never.solve(), Err(Error::NoSolution));
     }
 }
@@ -5,4 +5,11 @@ fn compilation() {
     t.compile_fail("tests/compilation/no_named_fields.rs");
     t.compile_fail("tests/compilation/no_variable.rs");
     t.compile_fail("tests/compilation/no_function_auto_compute.rs");
+    t.compile_fail("tests/compilation/unknown_unit.rs");
+    t.compile_fail("tests/compilation/equation_syntax.rs");
+    t.compile_fail("tests/compilation/equation_unknown_field.rs");
+    t.compile_fail("tests/compilation/equation_number_too_large.rs");
+    t.compile_fail("tests/compilation/unsupported_variable.rs");
+    t.compile_fail("tests/compilation/number_without_equation.rs");
+    t.compile_fail("tests/compilation/lossy_number.rs");
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+extern crate compute;
+
+#[macro_use]
+extern crate compute_macro;
+
+use compute::equation::EquationElement;
+
+fn main() {
+    #[derive(Equation)]
+    #[equation("total = first * 1e999")]
+    struct EquationNumberTooLarge {
+        #[variable]
+        total: EquationElement,
+        #[variable]
+        first: EquationElement,
+    }
+}
@@ -0,0 +1,19 @@
+extern crate compute;
+
+#[macro_use]
+extern crate compute_macro;
+
+use compute::equation::EquationElement;
+
+fn main() {
+    #[derive(Equation)]
+    #[equation("total = first + * second")]
+    struct EquationSyntax {
+        #[variable]
+        total: EquationElement,
+        #[variable]
+        first: EquationElement,
+        #[variable]
+        second: EquationElement,
+    }
+}
@@ -0,0 +1,17 @@
+extern crate compute;
+
+#[macro_use]
+extern crate compute_macro;
+
+fn main() {
+    #[derive(Equation)]
+    #[equation("total = count * size")]
+    struct LossyNumber {
+        #[variable]
+        total: Option<f64>,
+        #[variable]
+        count: u32,
+        #[variable]
+        size: u64,
+    }
+}
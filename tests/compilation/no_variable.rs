@@ -4,6 +4,7 @@ extern crate compute;
 extern crate compute_macro;
 
 use compute::equation::{EquationAutoCompute, EquationElement};
+use compute::relation::Relation;
 
 fn main() {
     #[derive(Equation)]
@@ -12,8 +13,8 @@ fn main() {
     }
 
     impl EquationAutoCompute for NoVariable {
-        fn auto_compute(&self) -> bool {
-            true
+        fn auto_compute(&self) -> Relation {
+            Relation::new(0., 0.)
         }
     }
 }
\ No newline at end of file
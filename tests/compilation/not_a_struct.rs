@@ -4,14 +4,15 @@ extern crate compute;
 extern crate compute_macro;
 
 use compute::equation::EquationAutoCompute;
+use compute::relation::Relation;
 
 fn main() {
     #[derive(Equation)]
     enum NotAStruct {}
 
     impl EquationAutoCompute for NotAStruct {
-        fn auto_compute(&self) -> bool {
-            true
+        fn auto_compute(&self) -> Relation {
+            Relation::new(0., 0.)
         }
     }
 }
\ No newline at end of file
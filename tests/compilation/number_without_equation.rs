@@ -0,0 +1,23 @@
+extern crate compute;
+
+#[macro_use]
+extern crate compute_macro;
+
+use compute::equation::EquationAutoCompute;
+use compute::relation::Relation;
+
+fn main() {
+    #[derive(Equation)]
+    struct NumberWithoutEquation {
+        #[variable]
+        total: Option<f64>,
+        #[variable]
+        part: f64,
+    }
+
+    impl EquationAutoCompute for NumberWithoutEquation {
+        fn auto_compute(&self) -> Relation {
+            Relation::new(0., 0.)
+        }
+    }
+}
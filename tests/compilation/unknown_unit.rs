@@ -0,0 +1,21 @@
+extern crate compute;
+
+#[macro_use]
+extern crate compute_macro;
+
+use compute::equation::{EquationAutoCompute, EquationElement};
+use compute::relation::Relation;
+
+fn main() {
+    #[derive(Equation)]
+    struct UnknownUnit {
+        #[variable(unit = "furlong")]
+        distance: EquationElement,
+    }
+
+    impl EquationAutoCompute for UnknownUnit {
+        fn auto_compute(&self) -> Relation {
+            Relation::new(0., 0.)
+        }
+    }
+}
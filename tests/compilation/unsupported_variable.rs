@@ -0,0 +1,17 @@
+extern crate compute;
+
+#[macro_use]
+extern crate compute_macro;
+
+fn main() {
+    type Meters = f64;
+
+    #[derive(Equation)]
+    #[equation("total = length * 2")]
+    struct UnsupportedVariable {
+        #[variable]
+        total: Option<f64>,
+        #[variable]
+        length: Meters,
+    }
+}
@@ -0,0 +1,244 @@
+// The units written like 'km/h', shared by the compute runtime and the derive macro checking them at compile time
+
+use std::ops::{Div, Mul};
+
+// Exponents of the SI base dimensions: length, mass, time, current, temperature, amount and luminosity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Dimension([i8; 7]);
+
+impl Dimension {
+    pub const NONE: Dimension = Dimension([0; 7]);
+    pub const LENGTH: Dimension = Dimension([1, 0, 0, 0, 0, 0, 0]);
+    pub const MASS: Dimension = Dimension([0, 1, 0, 0, 0, 0, 0]);
+    pub const TIME: Dimension = Dimension([0, 0, 1, 0, 0, 0, 0]);
+    pub const CURRENT: Dimension = Dimension([0, 0, 0, 1, 0, 0, 0]);
+    pub const TEMPERATURE: Dimension = Dimension([0, 0, 0, 0, 1, 0, 0]);
+    pub const AMOUNT: Dimension = Dimension([0, 0, 0, 0, 0, 1, 0]);
+    pub const LUMINOSITY: Dimension = Dimension([0, 0, 0, 0, 0, 0, 1]);
+
+    pub fn new(exponents: [i8; 7]) -> Self {
+        Dimension(exponents)
+    }
+
+    pub fn exponents(&self) -> [i8; 7] {
+        self.0
+    }
+
+    pub fn powi(&self, power: i8) -> Self {
+        Dimension(self.0.map(|exponent| exponent.saturating_mul(power)))
+    }
+}
+
+impl Mul for Dimension {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut exponents = self.0;
+        exponents.iter_mut().zip(rhs.0).for_each(|(exponent, other)| *exponent = exponent.saturating_add(other));
+        Dimension(exponents)
+    }
+}
+
+impl Div for Dimension {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.powi(-1)
+    }
+}
+
+// A unit parsed from its symbols, i.e. a dimension and the fraction converting its values to SI units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unit {
+    // Reduced, with a positive denominator
+    factor: (i128, i128),
+    dimension: Dimension,
+}
+
+const PREFIXES: [(&str, i32); 20] = [
+    ("Y", 24),
+    ("Z", 21),
+    ("E", 18),
+    ("P", 15),
+    ("T", 12),
+    ("G", 9),
+    ("M", 6),
+    ("k", 3),
+    ("h", 2),
+    ("da", 1),
+    ("d", -1),
+    ("c", -2),
+    ("m", -3),
+    ("µ", -6),
+    ("u", -6),
+    ("n", -9),
+    ("p", -12),
+    ("f", -15),
+    ("a", -18),
+    ("z", -21),
+];
+
+impl Unit {
+    pub const NONE: Unit = Unit {
+        factor: (1, 1),
+        dimension: Dimension::NONE,
+    };
+
+    // None if the unit is not written with known symbols, or if its factor overflows
+    pub fn parse(unit: &str) -> Option<Self> {
+        let unit: String = unit.chars().filter(|c| !c.is_whitespace()).collect();
+        if unit.is_empty() {
+            return None;
+        }
+
+        // Every '/' divides by the factor right after it, like 'kg / m / s^2'
+        let mut result = Self::NONE;
+        let mut divide = false;
+        let mut factor = String::new();
+        for c in unit.chars().chain(std::iter::once('*')) {
+            if c == '*' || c == '/' || c == '·' {
+                let parsed = Self::parse_power(&factor)?;
+                result = if divide { result.divide(parsed)? } else { result.multiply(parsed)? };
+                divide = c == '/';
+                factor.clear();
+            } else {
+                factor.push(c);
+            }
+        }
+        Some(result)
+    }
+
+    // The factor as '(numerator, denominator)'
+    pub fn factor(&self) -> (i128, i128) {
+        self.factor
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    fn multiply(self, rhs: Self) -> Option<Self> {
+        let ((a, b), (c, d)) = (self.factor, rhs.factor);
+        // Cross-reduced first, so that only the products of reduced fractions can overflow
+        let (g1, g2) = (gcd(a, d), gcd(c, b));
+        let numerator = (a / g1).checked_mul(c / g2)?;
+        let denominator = (b / g2).checked_mul(d / g1)?;
+        Some(Self {
+            factor: (numerator, denominator),
+            dimension: self.dimension * rhs.dimension,
+        })
+    }
+
+    fn inverse(self) -> Self {
+        let (numerator, denominator) = self.factor;
+        Self {
+            factor: (denominator, numerator),
+            dimension: Dimension::NONE / self.dimension,
+        }
+    }
+
+    fn divide(self, rhs: Self) -> Option<Self> {
+        self.multiply(rhs.inverse())
+    }
+
+    fn powi(self, power: i8) -> Option<Self> {
+        let unit = if power < 0 { self.inverse() } else { self };
+        (0..power.unsigned_abs()).try_fold(Self::NONE, |product, _| product.multiply(unit))
+    }
+
+    // A symbol with an optional integer power, like 's^2'
+    fn parse_power(factor: &str) -> Option<Self> {
+        let (symbol, power) = match factor.split_once('^') {
+            Some((symbol, power)) => (symbol, power.parse::<i8>().ok()?),
+            None => (factor, 1),
+        };
+        Self::parse_symbol(symbol)?.powi(power)
+    }
+
+    // A unit symbol, possibly with a prefix like 'k' in 'km'
+    fn parse_symbol(symbol: &str) -> Option<Self> {
+        if symbol == "1" {
+            return Some(Self::NONE);
+        }
+        if let Some(unit) = Self::named(symbol) {
+            return Some(unit);
+        }
+        PREFIXES
+            .iter()
+            .filter_map(|(prefix, power)| Some((symbol.strip_prefix(prefix)?, power)))
+            .find_map(|(symbol, power)| {
+                let unit = Self::named(symbol).filter(|_| Self::accepts_prefix(symbol))?;
+                let ten = Self {
+                    factor: (10, 1),
+                    dimension: Dimension::NONE,
+                };
+                let scale = (0..power.unsigned_abs()).try_fold(Self::NONE, |product, _| product.multiply(ten))?;
+                let scale = if *power < 0 { scale.inverse() } else { scale };
+                unit.multiply(scale)
+            })
+    }
+
+    fn accepts_prefix(symbol: &str) -> bool {
+        !matches!(symbol, "kg" | "min" | "h")
+    }
+
+    fn named(symbol: &str) -> Option<Self> {
+        let length = Dimension::LENGTH;
+        let mass = Dimension::MASS;
+        let time = Dimension::TIME;
+        let current = Dimension::CURRENT;
+        let force = mass * length / time.powi(2);
+        let energy = force * length;
+        let power = energy / time;
+        let (factor, dimension) = match symbol {
+            "m" => ((1, 1), length),
+            "g" => ((1, 1000), mass),
+            "kg" => ((1, 1), mass),
+            "s" => ((1, 1), time),
+            "min" => ((60, 1), time),
+            "h" => ((3600, 1), time),
+            "A" => ((1, 1), current),
+            "K" => ((1, 1), Dimension::TEMPERATURE),
+            "mol" => ((1, 1), Dimension::AMOUNT),
+            "cd" => ((1, 1), Dimension::LUMINOSITY),
+            "Hz" => ((1, 1), Dimension::NONE / time),
+            "N" => ((1, 1), force),
+            "Pa" => ((1, 1), force / length.powi(2)),
+            "J" => ((1, 1), energy),
+            "W" => ((1, 1), power),
+            "C" => ((1, 1), current * time),
+            "V" => ((1, 1), power / current),
+            "Ω" | "ohm" => ((1, 1), power / current.powi(2)),
+            "L" => ((1, 1000), length.powi(3)),
+            _ => return None,
+        };
+        Some(Self { factor, dimension })
+    }
+}
+
+// Positive, so that reduced fractions keep the sign of their numerator
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1) as i128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(Unit::parse("km/h").map(|unit| unit.factor()), Some((5, 18)));
+        assert_eq!(Unit::parse("cm^-2").map(|unit| unit.factor()), Some((10_000, 1)));
+        assert_eq!(Unit::parse("kg*m/s^2"), Unit::parse("N"));
+        assert_eq!(Unit::parse("1/h").map(|unit| unit.dimension()), Some(Dimension::NONE / Dimension::TIME));
+
+        assert_eq!(Unit::parse("furlong"), None);
+        assert_eq!(Unit::parse("m^"), None);
+        // 10^60 doesn't fit in a fraction of i128
+        assert_eq!(Unit::parse("km^20"), None);
+    }
+}